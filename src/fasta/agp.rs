@@ -0,0 +1,189 @@
+// build scaffold sequences from contigs and an AGP file
+
+use crate::utils::{
+    agp::{read_agp, AgpLine, Part, GAP_TYPES},
+    error::{self, AgpError},
+    revcomp::reverse_complement,
+};
+use anyhow::Result;
+use noodles_core::{Position, Region};
+use noodles_fasta::{
+    self as fasta,
+    io::{indexed_reader, IndexedReader},
+    record::{Definition, Sequence},
+    Record,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// the component sequences, read through a .fai index if the
+// contigs have one, otherwise held in memory
+enum Contigs {
+    Indexed {
+        reader: Box<IndexedReader<fasta::io::BufReader<File>>>,
+        lengths: HashMap<String, usize>,
+    },
+    InMemory(HashMap<String, Vec<u8>>),
+}
+
+impl Contigs {
+    fn open(path: &Path) -> Result<Self> {
+        let mut fai = path.as_os_str().to_owned();
+        fai.push(".fai");
+
+        match Path::new(&fai).exists() {
+            true => {
+                let reader = indexed_reader::Builder::default().build_from_path(path)?;
+                let lengths = reader
+                    .index()
+                    .as_ref()
+                    .iter()
+                    .map(|r| Ok((String::from_utf8(r.name().to_vec())?, r.length() as usize)))
+                    .collect::<Result<HashMap<_, _>>>()?;
+                Ok(Self::Indexed {
+                    reader: Box::new(reader),
+                    lengths,
+                })
+            }
+            false => {
+                let mut reader = crate::fasta_reader_file(path.to_path_buf())?;
+                let mut contigs = HashMap::new();
+                for record in reader.records() {
+                    let record = record?;
+                    contigs.insert(
+                        String::from_utf8(record.name().to_vec())?,
+                        record.sequence().as_ref().to_vec(),
+                    );
+                }
+                Ok(Self::InMemory(contigs))
+            }
+        }
+    }
+
+    fn len(&self, id: &str) -> Option<usize> {
+        match self {
+            Self::Indexed { lengths, .. } => lengths.get(id).cloned(),
+            Self::InMemory(contigs) => contigs.get(id).map(|s| s.len()),
+        }
+    }
+
+    // 1-based and closed, with beg >= 1 and beg <= end checked when
+    // the AGP was parsed, and end checked against the length
+    fn get(&mut self, id: &str, beg: usize, end: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::Indexed { reader, .. } => {
+                let region = Region::new(id, Position::try_from(beg)?..=Position::try_from(end)?);
+                Ok(reader.query(&region)?.sequence().as_ref().to_vec())
+            }
+            Self::InMemory(contigs) => Ok(contigs[id][beg - 1..end].to_vec()),
+        }
+    }
+}
+
+pub fn build(matches: &clap::ArgMatches) -> Result<()> {
+    let contigs = matches
+        .get_one::<PathBuf>("contigs")
+        .expect("required by clap");
+    let agp = matches.get_one::<PathBuf>("agp").expect("required by clap");
+
+    let mut contigs = Contigs::open(contigs)?;
+    let lines = read_agp(agp)?;
+    let mut writer = fasta::Writer::new(io::stdout());
+
+    let mut done = HashSet::new();
+    let mut start = 0;
+    for i in 1..=lines.len() {
+        // each object is a run of lines
+        if i < lines.len() && lines[i].object == lines[start].object {
+            continue;
+        }
+        let object = &lines[start].object;
+        if !done.insert(object.clone()) {
+            return Err(AgpError::SplitObject {
+                object: object.clone(),
+            }
+            .into());
+        }
+        let seq = build_object(&lines[start..i], &mut contigs)?;
+
+        let definition = Definition::new(object.as_str(), None);
+        writer
+            .write_record(&Record::new(definition, Sequence::from(seq)))
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+        start = i;
+    }
+    Ok(())
+}
+
+fn build_object(lines: &[AgpLine], contigs: &mut Contigs) -> Result<Vec<u8>> {
+    let mut seq = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let object = line.object.clone();
+        let part = line.part_number;
+        if part != i + 1 {
+            return Err(AgpError::PartNumber {
+                object,
+                part,
+                prev: i,
+            }
+            .into());
+        }
+        if line.object_beg != seq.len() + 1 {
+            return Err(AgpError::NotContiguous {
+                object,
+                part,
+                beg: line.object_beg,
+                prev: seq.len(),
+            }
+            .into());
+        }
+
+        match &line.part {
+            Part::Gap {
+                length, gap_type, ..
+            } => {
+                if !GAP_TYPES.contains(&gap_type.as_str()) {
+                    return Err(AgpError::GapType {
+                        object,
+                        part,
+                        gap_type: gap_type.clone(),
+                    }
+                    .into());
+                }
+                seq.extend(std::iter::repeat_n(b'N', *length));
+            }
+            Part::Component { id, beg, end, .. } => {
+                let len = match contigs.len(id) {
+                    Some(l) => l,
+                    None => {
+                        return Err(AgpError::MissingComponent {
+                            object,
+                            part,
+                            id: id.clone(),
+                        }
+                        .into())
+                    }
+                };
+                if *end > len {
+                    return Err(AgpError::ComponentRange {
+                        object,
+                        part,
+                        id: id.clone(),
+                        end: *end,
+                        len,
+                    }
+                    .into());
+                }
+                let component = contigs.get(id, *beg, *end)?;
+                // ?, 0 and na are placed as +
+                match line.is_reverse() {
+                    true => seq.extend(reverse_complement(&component)),
+                    false => seq.extend(component),
+                }
+            }
+        }
+    }
+    Ok(seq)
+}
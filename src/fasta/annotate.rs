@@ -0,0 +1,135 @@
+// join columns of a metadata table onto record
+// descriptions as key=value pairs
+
+use crate::{
+    fasta::filter::lines_from_file,
+    utils::{error, stdin},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+pub fn annotate(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let table = matches
+        .get_one::<PathBuf>("table")
+        .expect("required by clap");
+    let key = matches.get_one::<String>("key").expect("defaulted by clap");
+
+    let metadata = read_metadata(table, key)?;
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut missing = Vec::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    annotate_inner(&record?, &metadata, &mut missing, &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    annotate_inner(&record, &metadata, &mut missing, &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if !missing.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) had no metadata: {}",
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+// record ID to the key=value pairs of its row. The first line of
+// the table is a header naming each column.
+fn read_metadata(table: &PathBuf, key: &str) -> Result<HashMap<String, String>> {
+    let lines = lines_from_file(table)?;
+    let mut lines = lines
+        .iter()
+        .map(|l| l.trim_end_matches('\r'))
+        .filter(|l| !l.trim().is_empty());
+
+    let header: Vec<&str> = match lines.next() {
+        Some(h) => h.split('\t').map(|e| e.trim()).collect(),
+        None => bail!("[-]\tMetadata table is empty."),
+    };
+    let key_col = match header.iter().position(|h| *h == key) {
+        Some(k) => k,
+        None => bail!(
+            "[-]\tColumn {} not found in the metadata table header.",
+            key
+        ),
+    };
+
+    let mut metadata = HashMap::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').map(|e| e.trim()).collect();
+        let id = match fields.get(key_col) {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => continue,
+        };
+        let pairs = header
+            .iter()
+            .zip(fields.iter())
+            .enumerate()
+            .filter(|(i, (_, value))| *i != key_col && !value.is_empty())
+            .map(|(_, (name, value))| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if metadata.insert(id.clone(), pairs).is_some() {
+            eprintln!(
+                "[-]\t{} is in the metadata table more than once, using the last row.",
+                id
+            );
+        }
+    }
+    Ok(metadata)
+}
+
+fn annotate_inner(
+    record: &Record,
+    metadata: &HashMap<String, String>,
+    missing: &mut Vec<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+
+    let record = match metadata.get(&id) {
+        Some(pairs) if !pairs.is_empty() => {
+            let description = match record.description() {
+                Some(d) => format!("{} {}", std::str::from_utf8(d)?, pairs),
+                None => pairs.clone(),
+            };
+            let definition = Definition::new(id, Some(description.into_bytes()));
+            Record::new(definition, record.sequence().clone())
+        }
+        Some(_) => record.clone(),
+        None => {
+            missing.push(id);
+            record.clone()
+        }
+    };
+
+    writer
+        .write_record(&record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+// reverse translate protein to (degenerate) DNA
+
+use crate::{
+    utils::{error, iupac, stdin, translate},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::{
+    fs::File,
+    io::{prelude::*, BufReader},
+};
+
+pub fn back_translate(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let codon_usage = match matches.get_one::<PathBuf>("codon-usage") {
+        Some(path) => Some(read_codon_usage(path)?),
+        None => None,
+    };
+
+    let mut writer = fasta::Writer::new(io::stdout());
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    backtrans_inner(&record?, codon_usage.as_ref(), &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    backtrans_inner(&record, codon_usage.as_ref(), &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn backtrans_inner(
+    record: &Record,
+    codon_usage: Option<&HashMap<u8, [u8; 3]>>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let description = crate::fasta_id_description(record, FID::Description)?;
+
+    let mut seq = Vec::with_capacity(record.sequence().len() * 3);
+    // the number of distinct DNA sequences the output encodes
+    // overflows quickly, so sum its log10 over the codons instead
+    let mut log10_degeneracy = 0.0;
+    let mut codons = 0;
+
+    for aa in record.sequence().as_ref() {
+        let codon = match aa {
+            b'-' | b'.' => *b"---",
+            _ => match codon_usage.and_then(|c| c.get(&aa.to_ascii_uppercase())) {
+                Some(codon) => *codon,
+                None => translate::degenerate_codon(*aa).unwrap_or(*b"NNN"),
+            },
+        };
+        if codon != *b"---" {
+            let degeneracy: f64 = codon
+                .iter()
+                .map(|b| iupac::degeneracy(*b).max(1) as f64)
+                .product();
+            log10_degeneracy += degeneracy.log10();
+            codons += 1;
+        }
+        seq.extend_from_slice(&codon);
+    }
+
+    let definition = Definition::new(
+        id,
+        Some(
+            format!(
+                "{}:log10_degeneracy={:.2}:mean_codon_degeneracy={:.2}",
+                description,
+                log10_degeneracy,
+                // the geometric mean, so it multiplies back up to the total
+                match codons {
+                    0 => 1.0,
+                    _ => 10f64.powf(log10_degeneracy / codons as f64),
+                }
+            )
+            .into_bytes(),
+        ),
+    );
+    let record = Record::new(definition, seq.into());
+
+    writer
+        .write_record(&record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+
+    Ok(())
+}
+
+// read a two column table of codon and usage (count or frequency),
+// keeping the most used codon for each amino acid
+fn read_codon_usage(path: &PathBuf) -> Result<HashMap<u8, [u8; 3]>> {
+    let file = File::open(path)?;
+    let mut best: HashMap<u8, ([u8; 3], f64)> = HashMap::new();
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (codon, usage) = match (fields.next(), fields.next()) {
+            (Some(c), Some(u)) => (c.to_ascii_uppercase().replace('U', "T"), u),
+            _ => bail!("[-]\tCodon usage line {} needs a codon and a value.", i + 1),
+        };
+        let codon: [u8; 3] = match codon.as_bytes().try_into() {
+            Ok(c) => c,
+            Err(_) => bail!("[-]\tCodon usage line {}: {} is not a codon.", i + 1, codon),
+        };
+        let usage = match usage.parse::<f64>() {
+            Ok(u) => u,
+            Err(e) => bail!("[-]\tCodon usage line {}: {}", i + 1, e),
+        };
+
+        let aa = translate::translate(&codon)[0];
+        if aa == b'X' {
+            bail!(
+                "[-]\tCodon usage line {}: {} is not a codon.",
+                i + 1,
+                String::from_utf8_lossy(&codon)
+            );
+        }
+        match best.get(&aa) {
+            Some((_, u)) if *u >= usage => (),
+            _ => {
+                best.insert(aa, (codon, usage));
+            }
+        }
+    }
+
+    Ok(best.into_iter().map(|(aa, (c, _))| (aa, c)).collect())
+}
@@ -0,0 +1,166 @@
+// break scaffolds into contigs at runs of N
+
+use crate::{
+    fasta::extract::region_description,
+    utils::{
+        agp::{self, AgpLine},
+        error,
+        runs::{is_gap, runs},
+        stdin,
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::fs::File;
+use std::io::{self, prelude::*, BufWriter};
+use std::path::PathBuf;
+
+pub fn break_scaffolds(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let min_gap = *matches
+        .get_one::<usize>("min-gap")
+        .expect("defaulted by clap");
+    if min_gap == 0 {
+        bail!("[-]\t--min-gap must be at least 1.");
+    }
+
+    let mut agp = match matches.get_one::<PathBuf>("agp") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    if let Some(agp) = agp.as_mut() {
+        writeln!(agp, "{}", agp::VERSION)?;
+    }
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut all_n = Vec::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let basename = crate::get_basename_from_pathbuf(el)?;
+
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    break_inner(
+                        &record?,
+                        &basename,
+                        min_gap,
+                        &mut all_n,
+                        &mut writer,
+                        agp.as_mut(),
+                    )?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    break_inner(
+                        &record,
+                        "stdin",
+                        min_gap,
+                        &mut all_n,
+                        &mut writer,
+                        agp.as_mut(),
+                    )?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if let Some(mut agp) = agp {
+        agp.flush()?;
+    }
+    if !all_n.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) were all N and were skipped: {}",
+            all_n.len(),
+            all_n.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn break_inner(
+    record: &Record,
+    basename: &str,
+    min_gap: usize,
+    all_n: &mut Vec<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+    mut agp: Option<&mut BufWriter<File>>,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let description = crate::fasta_id_description(record, FID::Description)?;
+    let seq = record.sequence().as_ref();
+
+    // contigs and gaps in order, as (start, end, is a gap)
+    let mut parts = Vec::new();
+    let mut prev = 0;
+    for (start, end) in runs(seq, min_gap, is_gap) {
+        if start > prev {
+            parts.push((prev, start, false));
+        }
+        parts.push((start, end, true));
+        prev = end;
+    }
+    if seq.len() > prev {
+        parts.push((prev, seq.len(), false));
+    }
+
+    // an AGP object cannot start or end with a gap, so terminal
+    // runs of N are left out, and the object starts at the first contig
+    while parts.first().is_some_and(|p| p.2) {
+        parts.remove(0);
+    }
+    while parts.last().is_some_and(|p| p.2) {
+        parts.pop();
+    }
+    let offset = match parts.first() {
+        Some((start, _, _)) => *start,
+        None => {
+            if !seq.is_empty() {
+                all_n.push(id);
+            }
+            return Ok(());
+        }
+    };
+
+    for (i, (start, end, gap)) in parts.into_iter().enumerate() {
+        let contig_id = format!("{}:{}-{}", id, start + 1, end);
+        if let Some(agp) = agp.as_mut() {
+            let object_beg = start - offset + 1;
+            let line = match gap {
+                true => AgpLine::gap(&id, object_beg, i + 1, end - start),
+                false => AgpLine::component(&id, object_beg, i + 1, &contig_id, end - start),
+            };
+            writeln!(agp, "{}", line)?;
+        }
+        if gap {
+            continue;
+        }
+
+        let definition = Definition::new(
+            contig_id,
+            Some(region_description(&description, basename, start + 1, end).into_bytes()),
+        );
+        writer
+            .write_record(&Record::new(
+                definition,
+                Sequence::from(seq[start..end].to_vec()),
+            ))
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    }
+    Ok(())
+}
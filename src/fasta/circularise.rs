@@ -0,0 +1,113 @@
+// detect and trim the overlap between the ends of
+// circular contigs, flagging them as circular
+
+use crate::{
+    utils::{approx, error, stdin},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::io;
+
+struct Overlap {
+    min: usize,
+    max: Option<usize>,
+    mismatches: usize,
+}
+
+pub fn circularise(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let overlap = Overlap {
+        min: *matches
+            .get_one::<usize>("min-overlap")
+            .expect("defaulted by clap"),
+        max: matches.get_one::<usize>("max-overlap").cloned(),
+        mismatches: *matches
+            .get_one::<usize>("mismatches")
+            .expect("defaulted by clap"),
+    };
+    if overlap.min == 0 {
+        bail!("[-]\t--min-overlap must be at least 1.");
+    }
+
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut linear = Vec::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    circularise_inner(&record?, &overlap, &mut linear, &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    circularise_inner(&record, &overlap, &mut linear, &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if !linear.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) had no terminal overlap: {}",
+            linear.len(),
+            linear.join(", ")
+        );
+    }
+    Ok(())
+}
+
+// the longest suffix of the sequence matching its prefix, up
+// to half the sequence length
+fn terminal_overlap(seq: &[u8], overlap: &Overlap) -> Option<usize> {
+    let n = seq.len();
+    let max = overlap.max.unwrap_or(n).min(n / 2);
+    (overlap.min..=max)
+        .rev()
+        .find(|l| approx::within(&seq[n - l..], &seq[..*l], overlap.mismatches))
+}
+
+fn circularise_inner(
+    record: &Record,
+    overlap: &Overlap,
+    linear: &mut Vec<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let seq = record.sequence().as_ref();
+
+    let record = match terminal_overlap(seq, overlap) {
+        // keep the start, trimming the copy at the end
+        Some(l) => {
+            let description = crate::fasta_id_description(record, FID::Description)?;
+            let definition = Definition::new(
+                record.name(),
+                Some(format!("{}:circular=true:overlap={}", description, l).into_bytes()),
+            );
+            Record::new(definition, Sequence::from(seq[..seq.len() - l].to_vec()))
+        }
+        None => {
+            linear.push(crate::fasta_id_description(record, FID::Id)?);
+            record.clone()
+        }
+    };
+
+    writer
+        .write_record(&record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
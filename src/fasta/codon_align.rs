@@ -0,0 +1,130 @@
+// thread codons from coding sequences through a
+// protein alignment (like pal2nal)
+
+use crate::{
+    utils::{error, translate},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+pub fn codon_align(matches: &clap::ArgMatches) -> Result<()> {
+    let protein = matches
+        .get_one::<PathBuf>("protein")
+        .expect("required by clap");
+    let cds = matches.get_one::<PathBuf>("cds").expect("required by clap");
+
+    // coding sequences are looked up by ID
+    let mut cds_seqs = HashMap::new();
+    let mut reader = crate::fasta_reader_file(cds.to_path_buf())?;
+    for record in reader.records() {
+        let record = record?;
+        let id = crate::fasta_id_description(&record, FID::Id)?;
+        cds_seqs.insert(id, record.sequence().as_ref().to_vec());
+    }
+
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut nb_mismatched = 0;
+
+    let mut reader = crate::fasta_reader_file(protein.to_path_buf())?;
+    for record in reader.records() {
+        let record = record?;
+        let id = crate::fasta_id_description(&record, FID::Id)?;
+
+        let cds_seq = match cds_seqs.get(&id) {
+            Some(s) => s,
+            None => {
+                eprintln!("[-]\t{}: no coding sequence found.", id);
+                nb_mismatched += 1;
+                continue;
+            }
+        };
+
+        match thread_codons(record.sequence().as_ref(), cds_seq) {
+            Ok((aligned, mismatches)) => {
+                if !mismatches.is_empty() {
+                    eprintln!(
+                        "[-]\t{}: translation does not match protein at position(s) {}.",
+                        id,
+                        mismatches
+                            .iter()
+                            .map(|e| e.to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    );
+                    nb_mismatched += 1;
+                }
+                let definition =
+                    Definition::new(record.name(), record.description().map(Into::into));
+                writer
+                    .write_record(&Record::new(definition, aligned.into()))
+                    .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+            }
+            Err(e) => {
+                eprintln!("[-]\t{}: {}", id, e);
+                nb_mismatched += 1;
+            }
+        }
+    }
+
+    if nb_mismatched > 0 {
+        eprintln!(
+            "[-]\t{} record(s) did not match their coding sequence.",
+            nb_mismatched
+        );
+    }
+
+    Ok(())
+}
+
+// returns the codon alignment and the (1-based) alignment columns
+// where the codon does not translate to the aligned amino acid
+fn thread_codons(protein: &[u8], cds: &[u8]) -> Result<(Vec<u8>, Vec<usize>)> {
+    let nb_residues = protein
+        .iter()
+        .filter(|aa| !matches!(aa, b'-' | b'.'))
+        .count();
+
+    // a trailing stop codon in the CDS is often missing from the protein
+    let cds = if cds.len() == (nb_residues + 1) * 3
+        && translate::translate(&cds[cds.len() - 3..]) == b"*"
+    {
+        &cds[..cds.len() - 3]
+    } else {
+        cds
+    };
+
+    if cds.len() != nb_residues * 3 {
+        bail!(
+            "coding sequence length ({}) is not three times the number of residues ({}).",
+            cds.len(),
+            nb_residues
+        );
+    }
+
+    let mut aligned = Vec::with_capacity(protein.len() * 3);
+    let mut mismatches = Vec::new();
+    let mut codons = cds.chunks_exact(3);
+
+    for (i, aa) in protein.iter().enumerate() {
+        match aa {
+            b'-' | b'.' => aligned.extend_from_slice(b"---"),
+            _ => {
+                let codon = codons.next().expect("length checked above");
+                let translated = translate::translate(codon)[0];
+                if !aa.eq_ignore_ascii_case(&translated)
+                    && !matches!(aa, b'X' | b'x')
+                    && translated != b'X'
+                {
+                    mismatches.push(i + 1);
+                }
+                aligned.extend_from_slice(codon);
+            }
+        }
+    }
+
+    Ok((aligned, mismatches))
+}
@@ -0,0 +1,94 @@
+// remove duplicate records, by ID, sequence or canonical
+// sequence, keeping the first of each
+
+use crate::{
+    utils::{
+        error,
+        key::{record_key, KeyBy},
+        stdin,
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, Record};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, prelude::*, BufWriter};
+use std::path::PathBuf;
+
+pub fn dedup(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let by = KeyBy::new(matches.get_one::<String>("by").expect("defaulted by clap"));
+
+    let mut merged = match matches.get_one::<PathBuf>("merged") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    let mut writer = fasta::Writer::new(io::stdout());
+    // key to the ID of the record kept for it
+    let mut kept: HashMap<u64, String> = HashMap::new();
+    let mut removed = 0;
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    removed += dedup_inner(&record?, by, &mut kept, &mut writer, merged.as_mut())?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    removed += dedup_inner(&record, by, &mut kept, &mut writer, merged.as_mut())?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if let Some(mut merged) = merged {
+        merged.flush()?;
+    }
+    eprintln!(
+        "[-]\t{} record(s) kept, {} duplicate(s) removed.",
+        kept.len(),
+        removed
+    );
+    Ok(())
+}
+
+// returns the number of records removed
+fn dedup_inner(
+    record: &Record,
+    by: KeyBy,
+    kept: &mut HashMap<u64, String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+    merged: Option<&mut BufWriter<File>>,
+) -> Result<usize> {
+    let key = record_key(record, by)?;
+    let id = crate::fasta_id_description(record, FID::Id)?;
+
+    match kept.get(&key) {
+        Some(first) => {
+            if let Some(merged) = merged {
+                writeln!(merged, "{}\t{}", first, id)?;
+            }
+            Ok(1)
+        }
+        None => {
+            kept.insert(key, id);
+            writer
+                .write_record(record)
+                .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+            Ok(0)
+        }
+    }
+}
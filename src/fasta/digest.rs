@@ -0,0 +1,266 @@
+// simulate a restriction digest, cutting each
+// record at enzyme recognition sites
+
+use crate::utils::{approx, error, iupac, revcomp, stdin};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, prelude::*, BufWriter};
+use std::path::PathBuf;
+
+// name and recognition site, with ^ where the top strand is cut
+static ENZYMES: &[(&str, &str)] = &[
+    ("AluI", "AG^CT"),
+    ("ApeKI", "G^CWGC"),
+    ("BamHI", "G^GATCC"),
+    ("BglII", "A^GATCT"),
+    ("ClaI", "AT^CGAT"),
+    ("CviAII", "C^ATG"),
+    ("DpnII", "^GATC"),
+    ("EcoRI", "G^AATTC"),
+    ("EcoRV", "GAT^ATC"),
+    ("EcoT22I", "ATGCA^T"),
+    ("HaeIII", "GG^CC"),
+    ("HindIII", "A^AGCTT"),
+    ("HinP1I", "G^CGC"),
+    ("HpaII", "C^CGG"),
+    ("KpnI", "GGTAC^C"),
+    ("MboI", "^GATC"),
+    ("MseI", "T^TAA"),
+    ("MslI", "CAYNN^NNRTG"),
+    ("MspI", "C^CGG"),
+    ("NcoI", "C^CATGG"),
+    ("NdeI", "CA^TATG"),
+    ("NheI", "G^CTAGC"),
+    ("NlaIII", "CATG^"),
+    ("NotI", "GC^GGCCGC"),
+    ("NsiI", "ATGCA^T"),
+    ("PstI", "CTGCA^G"),
+    ("RsaI", "GT^AC"),
+    ("SacI", "GAGCT^C"),
+    ("SalI", "G^TCGAC"),
+    ("Sau3AI", "^GATC"),
+    ("SbfI", "CCTGCA^GG"),
+    ("SmaI", "CCC^GGG"),
+    ("SpeI", "A^CTAGT"),
+    ("SphI", "GCATG^C"),
+    ("TaqI", "T^CGA"),
+    ("XbaI", "T^CTAGA"),
+    ("XhoI", "C^TCGAG"),
+];
+
+struct Enzyme {
+    name: String,
+    site: Vec<u8>,
+    // top strand cut, as an offset into the site
+    cut: usize,
+}
+
+impl Enzyme {
+    // an enzyme name, or a custom site like G^AATTC
+    fn parse(s: &str) -> Result<Self> {
+        let (name, site) = match ENZYMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(s)) {
+            Some((n, site)) => (n.to_string(), *site),
+            None => (s.to_string(), s),
+        };
+        let cut = match site.find('^') {
+            Some(c) => c,
+            None => bail!(
+                "[-]\t{} is not a known enzyme, or a site with a ^ marking the cut (e.g. G^AATTC).",
+                s
+            ),
+        };
+        let site = site.replacen('^', "", 1).into_bytes();
+        if site.is_empty() {
+            bail!("[-]\t{} has an empty recognition site.", s);
+        }
+        if let Some(c) = site.iter().find(|c| iupac::to_mask(**c) == 0) {
+            bail!(
+                "[-]\t{} has a non-IUPAC character {} in its recognition site.",
+                s,
+                *c as char
+            );
+        }
+        Ok(Self { name, site, cut })
+    }
+}
+
+pub fn digest(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let enzymes = matches
+        .get_one::<String>("enzyme")
+        .expect("required by clap")
+        .split(',')
+        .map(|e| Enzyme::parse(e.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    let circular = matches.get_flag("circular");
+
+    let mut table = match matches.get_one::<PathBuf>("table") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    let mut writer = fasta::Writer::new(io::stdout());
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    digest_inner(&record?, &enzymes, circular, &mut writer, table.as_mut())?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    digest_inner(&record, &enzymes, circular, &mut writer, table.as_mut())?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if let Some(mut table) = table {
+        table.flush()?;
+    }
+    Ok(())
+}
+
+// top strand cut positions, and the enzyme(s) cutting there
+fn cut_sites(seq: &[u8], enzymes: &[Enzyme], circular: bool) -> BTreeMap<usize, String> {
+    let n = seq.len();
+    let mut cuts: BTreeMap<usize, String> = BTreeMap::new();
+
+    // circular records are searched with the start appended, for
+    // sites spanning the origin; linear ones are searched in place
+    let wrapped;
+    let target = match circular {
+        true => {
+            let longest = enzymes.iter().map(|e| e.site.len()).max().unwrap_or(1);
+            wrapped = [seq, &seq[..(longest - 1).min(n)]].concat();
+            &wrapped[..]
+        }
+        false => seq,
+    };
+
+    for enzyme in enzymes {
+        let len = enzyme.site.len();
+        // each site is found once, from a start within the record
+        let starts = |site: &[u8]| {
+            approx::hamming(site, target, 0)
+                .into_iter()
+                .map(|h| h.start)
+                .filter(|s| *s < n)
+                .collect::<Vec<_>>()
+        };
+
+        let mut positions: Vec<usize> = starts(&enzyme.site)
+            .into_iter()
+            .map(|s| s + enzyme.cut)
+            .collect();
+        // non-palindromic sites can be on the reverse strand, where this
+        // is the bottom strand cut. Overhangs are not modelled, so the
+        // fragments are split there on both strands
+        let site_rc = revcomp::reverse_complement(&enzyme.site);
+        if site_rc != enzyme.site {
+            positions.extend(starts(&site_rc).into_iter().map(|s| s + len - enzyme.cut));
+        }
+
+        for pos in positions {
+            let pos = match circular {
+                true => pos % n,
+                false if pos == 0 || pos >= n => continue,
+                false => pos,
+            };
+            cuts.entry(pos)
+                .and_modify(|e| {
+                    if !e.split(',').any(|x| x == enzyme.name) {
+                        e.push(',');
+                        e.push_str(&enzyme.name);
+                    }
+                })
+                .or_insert_with(|| enzyme.name.clone());
+        }
+    }
+    cuts
+}
+
+fn digest_inner(
+    record: &Record,
+    enzymes: &[Enzyme],
+    circular: bool,
+    writer: &mut fasta::Writer<io::Stdout>,
+    mut table: Option<&mut BufWriter<File>>,
+) -> Result<()> {
+    let id = String::from_utf8(record.name().to_vec())?;
+    let description = String::from_utf8(record.description().unwrap_or_default().to_vec())?;
+    let seq = record.sequence().as_ref();
+    let n = seq.len();
+    if n == 0 {
+        return Ok(());
+    }
+
+    let cuts: Vec<(usize, String)> = cut_sites(seq, enzymes, circular).into_iter().collect();
+
+    // (start, end, left enzyme, right enzyme), where a circular
+    // fragment across the origin has end < start
+    let mut fragments = Vec::new();
+    if circular && !cuts.is_empty() {
+        for (i, (start, left)) in cuts.iter().enumerate() {
+            let (end, right) = &cuts[(i + 1) % cuts.len()];
+            fragments.push((*start, *end, left.as_str(), right.as_str()));
+        }
+    } else {
+        // linear records, or circular ones without a site
+        let ends = if circular { "circular" } else { "end" };
+        let mut prev = (0, ends);
+        for (pos, name) in cuts.iter() {
+            fragments.push((prev.0, *pos, prev.1, name.as_str()));
+            prev = (*pos, name.as_str());
+        }
+        fragments.push((prev.0, n, prev.1, ends));
+    }
+
+    for (i, (start, end, left, right)) in fragments.iter().enumerate() {
+        let frag_seq = match start < end {
+            true => seq[*start..*end].to_vec(),
+            // wraps around the origin (or a single cut in a circle)
+            false => [&seq[*start..], &seq[..*end]].concat(),
+        };
+        // a cut at the origin ends a fragment at the last base
+        let end = if *end == 0 { n } else { *end };
+        let name = format!("{}_{}", id, i + 1);
+        let definition = Definition::new(
+            name.clone(),
+            Some(format!("{}:{}-{}", description, start + 1, end).into_bytes()),
+        );
+        if let Some(table) = table.as_mut() {
+            writeln!(
+                table,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                id,
+                name,
+                start + 1,
+                end,
+                frag_seq.len(),
+                left,
+                right
+            )?;
+        }
+        writer
+            .write_record(&Record::new(definition, Sequence::from(frag_seq)))
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    }
+    Ok(())
+}
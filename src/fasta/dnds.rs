@@ -0,0 +1,245 @@
+// pairwise synonymous and non-synonymous substitution
+// rates from an in-frame codon alignment
+
+use crate::{
+    utils::{
+        error, stdin,
+        translate::{self, CodonDifferences},
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::Record;
+
+#[derive(Clone, Copy)]
+enum Method {
+    // Nei & Gojobori (1986) with a Jukes-Cantor correction
+    NeiGojobori,
+    // modified Nei & Gojobori: sites weighted by the transition/
+    // transversion ratio, and a Kimura two parameter correction
+    ModifiedNeiGojobori,
+}
+
+pub fn dnds(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let method = match matches
+        .get_one::<String>("method")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "mng" => Method::ModifiedNeiGojobori,
+        _ => Method::NeiGojobori,
+    };
+
+    // an alignment has to be held in memory
+    let mut alignment = Vec::new();
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    alignment.push(record?);
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    alignment.push(record);
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    check_alignment(&alignment)?;
+
+    println!("seq1\tseq2\tS\tN\tSd\tNd\tpS\tpN\tkappa\tdS\tdN\tdN/dS");
+    for (i, a) in alignment.iter().enumerate() {
+        for b in alignment.iter().skip(i + 1) {
+            let id_a = crate::fasta_id_description(a, FID::Id)?;
+            let id_b = crate::fasta_id_description(b, FID::Id)?;
+            let res = pairwise(a.sequence().as_ref(), b.sequence().as_ref(), method);
+            println!(
+                "{}\t{}\t{:.4}\t{:.4}\t{:.4}\t{:.4}\t{}\t{}\t{}\t{}\t{}\t{}",
+                id_a,
+                id_b,
+                res.syn_sites,
+                res.nonsyn_sites,
+                res.diffs.syn(),
+                res.diffs.nonsyn(),
+                format_rate(res.ps),
+                format_rate(res.pn),
+                format_rate(res.kappa),
+                format_rate(res.ds),
+                format_rate(res.dn),
+                format_rate(res.dn / res.ds)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn check_alignment(alignment: &[Record]) -> Result<()> {
+    if alignment.len() < 2 {
+        bail!("[-]\tAt least two sequences are needed for pairwise dN/dS.");
+    }
+    let len = alignment[0].sequence().len();
+    for record in alignment.iter() {
+        if record.sequence().len() != len {
+            bail!(
+                "[-]\t{} has length {}, but the alignment has length {}.",
+                crate::fasta_id_description(record, FID::Id)?,
+                record.sequence().len(),
+                len
+            );
+        }
+    }
+    if !len.is_multiple_of(3) {
+        bail!(
+            "[-]\tAlignment length ({}) is not a multiple of three.",
+            len
+        );
+    }
+    Ok(())
+}
+
+struct Pairwise {
+    syn_sites: f64,
+    nonsyn_sites: f64,
+    diffs: CodonDifferences,
+    ps: f64,
+    pn: f64,
+    kappa: f64,
+    ds: f64,
+    dn: f64,
+}
+
+fn pairwise(a: &[u8], b: &[u8], method: Method) -> Pairwise {
+    // codons with gaps, ambiguous bases or stops
+    // in either sequence are skipped
+    let codons: Vec<(&[u8], &[u8])> = a
+        .chunks_exact(3)
+        .zip(b.chunks_exact(3))
+        .filter(|(ca, cb)| {
+            matches!(
+                (translate::translate_codon(ca), translate::translate_codon(cb)),
+                (Some(x), Some(y)) if x != b'*' && y != b'*'
+            )
+        })
+        .collect();
+
+    let kappa = match method {
+        Method::NeiGojobori => 1.0,
+        Method::ModifiedNeiGojobori => estimate_kappa(&codons),
+    };
+
+    let mut syn_sites = 0.0;
+    let mut nonsyn_sites = 0.0;
+    let mut diffs = CodonDifferences::default();
+
+    for (ca, cb) in codons.iter() {
+        let (sa, na) = translate::codon_sites(ca, kappa).expect("filtered above");
+        let (sb, nb) = translate::codon_sites(cb, kappa).expect("filtered above");
+        syn_sites += (sa + sb) / 2.0;
+        nonsyn_sites += (na + nb) / 2.0;
+        diffs += translate::codon_differences(ca, cb).expect("filtered above");
+    }
+
+    let ps = diffs.syn() / syn_sites;
+    let pn = diffs.nonsyn() / nonsyn_sites;
+
+    let (ds, dn) = match method {
+        Method::NeiGojobori => (jukes_cantor(ps), jukes_cantor(pn)),
+        Method::ModifiedNeiGojobori => (
+            kimura(diffs.syn_ts / syn_sites, diffs.syn_tv / syn_sites),
+            kimura(
+                diffs.nonsyn_ts / nonsyn_sites,
+                diffs.nonsyn_tv / nonsyn_sites,
+            ),
+        ),
+    };
+
+    Pairwise {
+        syn_sites,
+        nonsyn_sites,
+        diffs,
+        ps,
+        pn,
+        kappa,
+        ds,
+        dn,
+    }
+}
+
+// transition/transversion ratio from the third positions of
+// codon pairs in the same four-fold degenerate family
+fn estimate_kappa(codons: &[(&[u8], &[u8])]) -> f64 {
+    let mut sites: f64 = 0.0;
+    let mut ts: f64 = 0.0;
+    let mut tv: f64 = 0.0;
+
+    for (ca, cb) in codons.iter() {
+        if !ca[..2].eq_ignore_ascii_case(&cb[..2]) || !is_fourfold(ca) {
+            continue;
+        }
+        sites += 1.0;
+        let (x, y) = (ca[2].to_ascii_uppercase(), cb[2].to_ascii_uppercase());
+        if x == y || matches!((x, y), (b'T', b'U') | (b'U', b'T')) {
+            continue;
+        }
+        if translate::is_transition(x, y) {
+            ts += 1.0;
+        } else {
+            tv += 1.0;
+        }
+    }
+
+    let (p, q) = (ts / sites, tv / sites);
+    let s = -0.5 * (1.0 - 2.0 * p - q).ln() + 0.25 * (1.0 - 2.0 * q).ln();
+    let v = -0.5 * (1.0 - 2.0 * q).ln();
+    let kappa = 2.0 * s / v;
+
+    if kappa.is_finite() && kappa > 0.0 {
+        kappa
+    } else {
+        1.0
+    }
+}
+
+// all four bases at the third position code for the same amino acid
+fn is_fourfold(codon: &[u8]) -> bool {
+    let aa = translate::translate_codon(codon);
+    [b'A', b'C', b'G', b'T']
+        .iter()
+        .all(|base| translate::translate_codon(&[codon[0], codon[1], *base]) == aa)
+}
+
+fn jukes_cantor(p: f64) -> f64 {
+    -0.75 * (1.0 - 4.0 * p / 3.0).ln()
+}
+
+// Kimura (1980) two parameter distance from proportions of
+// transitions (p) and transversions (q)
+fn kimura(p: f64, q: f64) -> f64 {
+    -0.5 * (1.0 - 2.0 * p - q).ln() - 0.25 * (1.0 - 2.0 * q).ln()
+}
+
+// saturated or undefined estimates are reported as NA
+fn format_rate(r: f64) -> String {
+    if r == 0.0 {
+        // no -0.0000 from the log corrections
+        "0.0000".into()
+    } else if r.is_finite() {
+        format!("{:.4}", r)
+    } else {
+        "NA".into()
+    }
+}
@@ -68,8 +68,8 @@ fn extract_inner(
     basename: String,
     writer: &mut fasta::io::Writer<io::Stdout>,
 ) -> Result<()> {
-    let id = crate::fasta_id_description(&record, FID::Id)?;
-    let description = crate::fasta_id_description(&record, FID::Description)?;
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let description = crate::fasta_id_description(record, FID::Description)?;
 
     let start = Position::try_from(parsed_region[0])?;
     let end = Position::try_from(parsed_region[1])?;
@@ -81,10 +81,8 @@ fn extract_inner(
         None => bail!(error::RegionError::SeqExtractError),
     };
     // write to stdout
-    let description = format!(
-        "{}:{}:{}-{}",
-        description, basename, parsed_region[0], parsed_region[1]
-    );
+    let description =
+        region_description(&description, &basename, parsed_region[0], parsed_region[1]);
 
     let definition = Definition::new(id, Some(description.into_bytes()));
 
@@ -96,3 +94,14 @@ fn extract_inner(
 
     Ok(())
 }
+
+// the description of a (1-based, inclusive) region
+// taken out of a record
+pub(crate) fn region_description(
+    description: &str,
+    basename: &str,
+    start: usize,
+    end: usize,
+) -> String {
+    format!("{}:{}:{}-{}", description, basename, start, end)
+}
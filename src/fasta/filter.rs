@@ -1,26 +1,247 @@
-use crate::utils::{error, stdin};
+use crate::{
+    utils::{error, stdin},
+    FID,
+};
 use anyhow::{bail, Result};
-use noodles_fasta::{self as fasta, record::Definition};
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use regex::RegexSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{
     fs::File,
     io::{prelude::*, BufReader},
 };
 
-fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
+pub(crate) fn lines_from_file(filename: impl AsRef<Path>) -> Result<Vec<String>> {
+    let file = File::open(filename)?;
     let buf = BufReader::new(file);
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+    Ok(buf.lines().collect::<Result<Vec<_>, _>>()?)
+}
+
+// which part of the header the IDs are matched against
+#[derive(Clone, Copy)]
+enum Target {
+    Id,
+    Description,
+    Full,
+}
+
+// how a list entry is compared with the header
+enum Mode {
+    Exact(HashMap<String, usize>),
+    Prefix(HashMap<String, usize>),
+    Glob(RegexSet),
+}
+
+struct Matcher {
+    ids: Vec<String>,
+    mode: Mode,
+    target: Target,
+    inverse: bool,
+    // whether each entry of the list matched a record
+    found: Vec<bool>,
+}
+
+impl Matcher {
+    fn new(
+        ids: Vec<String>,
+        target: Target,
+        prefix: bool,
+        glob: bool,
+        inverse: bool,
+    ) -> Result<Self> {
+        let index = || {
+            ids.iter()
+                .enumerate()
+                .map(|(i, id)| (id.clone(), i))
+                .collect::<HashMap<_, _>>()
+        };
+        let mode = match (prefix, glob) {
+            (_, true) => Mode::Glob(
+                RegexSet::new(ids.iter().map(|g| glob_to_regex(g)))
+                    .map_err(|_| error::RegexError::CouldNotCompile)?,
+            ),
+            (true, false) => Mode::Prefix(index()),
+            (false, false) => Mode::Exact(index()),
+        };
+        Ok(Self {
+            found: vec![false; ids.len()],
+            ids,
+            mode,
+            target,
+            inverse,
+        })
+    }
+
+    // None if the record is filtered out, otherwise the position
+    // in the list of the first entry it matched
+    fn position(&mut self, record: &Record) -> Result<Option<usize>> {
+        let text = match self.target {
+            Target::Id => crate::fasta_id_description(record, FID::Id)?,
+            Target::Description => crate::fasta_id_description(record, FID::Description)?,
+            Target::Full => match record.description() {
+                Some(_) => crate::fasta_id_description(record, FID::Both(" ".into()))?,
+                None => crate::fasta_id_description(record, FID::Id)?,
+            },
+        };
+
+        let hits: Vec<usize> = match &self.mode {
+            Mode::Exact(ids) => ids.get(&text).into_iter().cloned().collect(),
+            Mode::Prefix(ids) => text
+                .char_indices()
+                .map(|(i, _)| i)
+                .skip(1)
+                .chain(std::iter::once(text.len()))
+                .filter_map(|i| ids.get(&text[..i]).cloned())
+                .collect(),
+            Mode::Glob(set) => set.matches(&text).into_iter().collect(),
+        };
+        for i in hits.iter() {
+            self.found[*i] = true;
+        }
+        Ok(match (hits.iter().min(), self.inverse) {
+            (Some(i), false) => Some(*i),
+            (None, true) => Some(self.ids.len()),
+            _ => None,
+        })
+    }
+
+    fn report_missing(&self) {
+        let missing: Vec<&str> = self
+            .ids
+            .iter()
+            .zip(self.found.iter())
+            .filter(|(_, found)| !**found)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        if !missing.is_empty() {
+            eprintln!(
+                "[-]\t{} ID(s) were not found: {}",
+                missing.len(),
+                missing.join(", ")
+            );
+        }
+    }
+}
+
+// a shell style glob (*, ? and [...]) as an anchored regex
+fn glob_to_regex(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut re = String::from("^");
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            // a character class, if it is closed
+            '[' if chars[i + 1..].contains(&']') => {
+                let close = i + 1 + chars[i + 1..].iter().position(|c| *c == ']').unwrap();
+                let mut class = chars[i + 1..close].iter().peekable();
+                re.push('[');
+                if class.next_if_eq(&&'!').is_some() {
+                    re.push('^');
+                }
+                for c in class {
+                    if matches!(c, '\\' | '[' | '^' | '&' | '~') {
+                        re.push('\\');
+                    }
+                    re.push(*c);
+                }
+                re.push(']');
+                i = close;
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    re.push('$');
+    re
+}
+
+// the list of IDs, from a file (or stdin), optionally a single
+// column of it, or from the headers of another fasta file
+fn read_ids(matches: &clap::ArgMatches) -> Result<Vec<String>> {
+    let lines = match (
+        matches.get_one::<String>("file"),
+        matches.get_one::<PathBuf>("ids-from"),
+    ) {
+        (_, Some(fasta)) => {
+            let mut reader = crate::fasta_reader_file(fasta.to_path_buf())?;
+            let mut names = Vec::new();
+            for record in reader.records() {
+                names.push(String::from_utf8(record?.name().to_vec())?);
+            }
+            return Ok(names);
+        }
+        (Some(file), None) if file == "-" => {
+            io::stdin().lock().lines().collect::<Result<Vec<_>, _>>()?
+        }
+        (Some(file), None) => lines_from_file(file)?,
+        (None, None) => bail!("[-]\tSupply IDs with --file or --ids-from."),
+    };
+
+    match matches.get_one::<usize>("column") {
+        Some(0) => bail!("[-]\tColumns are numbered from 1."),
+        // comment and header lines (e.g. BED track lines) are skipped
+        Some(column) => Ok(lines
+            .iter()
+            .filter(|l| {
+                !l.starts_with('#') && !l.starts_with("track ") && !l.starts_with("browser ")
+            })
+            .filter_map(|l| l.split('\t').nth(column - 1))
+            .map(String::from)
+            .collect()),
+        None => Ok(lines),
+    }
+}
+
+fn write_or_keep(
+    record: Record,
+    position: usize,
+    kept: Option<&mut Vec<(usize, Record)>>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    match kept {
+        Some(kept) => kept.push((position, record)),
+        None => writer
+            .write_record(&record)
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?,
+    }
+    Ok(())
 }
 
 pub fn filter_sequences(matches: &clap::ArgMatches) -> Result<()> {
     let input_file = crate::get_fasta_files(matches);
-    let id_file = matches.get_one::<String>("file").unwrap();
-    // just read file into memory for ease...
-    let ids = lines_from_file(id_file);
+    let target = match matches
+        .get_one::<String>("match")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "description" => Target::Description,
+        "full" => Target::Full,
+        _ => Target::Id,
+    };
+    // just read the IDs into memory for ease, ignoring
+    // surrounding whitespace, blank lines and repeats
+    let mut seen = HashSet::new();
+    let ids: Vec<String> = read_ids(matches)?
+        .iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty() && seen.insert(l.clone()))
+        .collect();
+    let mut matcher = Matcher::new(
+        ids,
+        target,
+        matches.get_flag("prefix"),
+        matches.get_flag("glob"),
+        matches.get_flag("inverse"),
+    )?;
+    // records are held back to be written in the order of the list
+    let mut kept = match matches.get_flag("keep-order") {
+        true => Some(Vec::new()),
+        false => None,
+    };
     // writer here?
     let mut writer = fasta::Writer::new(io::stdout());
 
@@ -34,8 +255,8 @@ pub fn filter_sequences(matches: &clap::ArgMatches) -> Result<()> {
                 for record in reader.records() {
                     let record = record?;
 
-                    let id = String::from_utf8(record.name().to_vec())?;
-                    if ids.contains(&id.to_owned()) {
+                    if let Some(position) = matcher.position(&record)? {
+                        let id = String::from_utf8(record.name().to_vec())?;
                         let description = record
                             .description()
                             .map(|d| basename.clone() + std::str::from_utf8(d).unwrap())
@@ -44,25 +265,23 @@ pub fn filter_sequences(matches: &clap::ArgMatches) -> Result<()> {
 
                         let record = fasta::Record::new(definition, record.sequence().to_owned());
 
-                        writer
-                            .write_record(&record)
-                            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+                        write_or_keep(record, position, kept.as_mut(), &mut writer)?;
                     }
                 }
             }
         }
-        // read from stdin
+        // read from stdin, unless the IDs came from there
+        None if matches.get_one::<String>("file").is_some_and(|f| f == "-") => {
+            bail!("[-]\tIDs are read from stdin, so supply the fasta file(s) as arguments.")
+        }
         None => match stdin::is_stdin() {
             true => {
                 let mut records = fasta::io::Reader::new(BufReader::new(io::stdin()));
 
                 let mut records = records.records();
                 while let Some(Ok(record)) = records.next() {
-                    let id = String::from_utf8(record.name().to_vec())?;
-                    if ids.contains(&id.to_owned()) {
-                        writer
-                            .write_record(&record)
-                            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+                    if let Some(position) = matcher.position(&record)? {
+                        write_or_keep(record, position, kept.as_mut(), &mut writer)?;
                     }
                 }
             }
@@ -71,5 +290,17 @@ pub fn filter_sequences(matches: &clap::ArgMatches) -> Result<()> {
             }
         },
     }
+
+    if let Some(mut kept) = kept {
+        // stable, so records matching the same entry stay in fasta order
+        kept.sort_by_key(|(position, _)| *position);
+        for (_, record) in kept.iter() {
+            writer
+                .write_record(record)
+                .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+        }
+    }
+
+    matcher.report_missing();
     Ok(())
 }
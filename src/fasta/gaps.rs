@@ -0,0 +1,84 @@
+// BED intervals of gaps (runs of N) or soft-masked
+// (lowercase) bases, or a summary of them per record
+
+use crate::{
+    utils::{
+        error,
+        runs::{is_gap, runs},
+        stdin,
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::Record;
+
+pub fn gaps(matches: &clap::ArgMatches) -> Result<()> {
+    intervals(matches, is_gap)
+}
+
+pub fn masked(matches: &clap::ArgMatches) -> Result<()> {
+    intervals(matches, |base| base.is_ascii_lowercase())
+}
+
+fn intervals(matches: &clap::ArgMatches, pred: fn(u8) -> bool) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let min_len = *matches
+        .get_one::<usize>("min-len")
+        .expect("defaulted by clap");
+    let summary = matches.get_flag("summary");
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    intervals_inner(&record?, min_len, pred, summary)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    intervals_inner(&record, min_len, pred, summary)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn intervals_inner(
+    record: &Record,
+    min_len: usize,
+    pred: fn(u8) -> bool,
+    summary: bool,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let seq = record.sequence().as_ref();
+    let runs = runs(seq, min_len, pred);
+
+    match summary {
+        // count, total length and fraction of the record
+        true => {
+            let total: usize = runs.iter().map(|(s, e)| e - s).sum();
+            let fraction = match seq.is_empty() {
+                true => 0.0,
+                false => total as f64 / seq.len() as f64,
+            };
+            println!("{}\t{}\t{}\t{:.4}", id, runs.len(), total, fraction);
+        }
+        false => {
+            for (start, end) in runs {
+                println!("{}\t{}\t{}", id, start, end);
+            }
+        }
+    }
+    Ok(())
+}
@@ -49,7 +49,7 @@ pub fn get_gc(matches: &clap::ArgMatches) -> Result<()> {
 }
 
 // see https://github.com/rust-bio/rust-bio/blob/master/src/seq_analysis/gc.rs
-fn gc_content<C: Borrow<u8>, T: IntoIterator<Item = C>>(sequence: T) -> f32 {
+pub(crate) fn gc_content<C: Borrow<u8>, T: IntoIterator<Item = C>>(sequence: T) -> f32 {
     let (l, count) = sequence
         .into_iter()
         .fold((0usize, 0usize), |(l, count), n| match *n.borrow() {
@@ -0,0 +1,123 @@
+// find oligos in sequences on both strands, allowing
+// mismatches, indels and IUPAC degeneracy
+
+use crate::utils::{approx, error, revcomp, stdin};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition};
+use std::io::BufRead;
+use std::path::PathBuf;
+
+pub(crate) struct Oligo {
+    pub(crate) name: String,
+    pub(crate) forward: Vec<u8>,
+    pub(crate) reverse: Vec<u8>,
+}
+
+impl Oligo {
+    pub(crate) fn new(name: String, seq: &[u8]) -> Self {
+        Self {
+            name,
+            forward: seq.to_vec(),
+            reverse: revcomp::reverse_complement(seq),
+        }
+    }
+}
+
+pub fn grep_seq(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let k = *matches
+        .get_one::<usize>("mismatches")
+        .expect("defaulted by clap");
+    let indels = matches.get_flag("indels");
+
+    let mut oligos: Vec<Oligo> = matches
+        .get_many::<String>("pattern")
+        .map(|p| p.map(|e| Oligo::new(e.clone(), e.as_bytes())).collect())
+        .unwrap_or_default();
+    if let Some(path) = matches.get_one::<PathBuf>("oligos") {
+        let mut reader = crate::fasta_reader_file(path.to_path_buf())?;
+        for record in reader.records() {
+            let record = record?;
+            let name = String::from_utf8(record.name().to_vec())?;
+            oligos.push(Oligo::new(name, record.sequence().as_ref()));
+        }
+    }
+    if oligos.is_empty() {
+        bail!("[-]\tNo oligos to search for.");
+    }
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                grep_reader(&mut reader, &oligos, k, indels)?;
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                grep_reader(&mut reader, &oligos, k, indels)?;
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn grep_reader<R: BufRead>(
+    reader: &mut fasta::io::Reader<R>,
+    oligos: &[Oligo],
+    k: usize,
+    indels: bool,
+) -> Result<()> {
+    let mut definition = String::new();
+    let mut seq = Vec::new();
+
+    loop {
+        definition.clear();
+        if reader.read_definition(&mut definition)? == 0 {
+            break;
+        }
+        let definition: Definition = definition.parse()?;
+        let id = String::from_utf8(definition.name().to_vec())?;
+
+        seq.clear();
+        reader.read_sequence(&mut seq)?;
+
+        // the reverse complemented oligo is searched for on the
+        // forward strand, so the target is never copied
+        let mut hits = Vec::new();
+        for oligo in oligos {
+            for (pattern, strand) in [(&oligo.forward, '+'), (&oligo.reverse, '-')] {
+                let found = match indels {
+                    true => approx::levenshtein(pattern, &seq, k),
+                    false => approx::hamming(pattern, &seq, k),
+                };
+                hits.extend(found.into_iter().map(|h| (h, &oligo.name, strand)));
+            }
+        }
+
+        hits.sort_by_key(|(h, _, _)| (h.start, h.end));
+        for (hit, name, strand) in hits {
+            let matched = match strand {
+                '+' => seq[hit.start..hit.end].to_vec(),
+                _ => revcomp::reverse_complement(&seq[hit.start..hit.end]),
+            };
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                id,
+                hit.start,
+                hit.end,
+                name,
+                hit.dist,
+                strand,
+                String::from_utf8_lossy(&matched)
+            );
+        }
+    }
+    Ok(())
+}
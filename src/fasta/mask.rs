@@ -0,0 +1,146 @@
+// hard or soft mask BED intervals of records, and
+// unmask or hard mask soft-masked sequence
+
+use crate::{
+    utils::{bed::read_bed, error, stdin},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Sequence, Record};
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy)]
+enum Mode {
+    // replace with N
+    Hard,
+    // lowercase
+    Soft,
+}
+
+pub fn mask(matches: &clap::ArgMatches) -> Result<()> {
+    let bed = matches.get_one::<PathBuf>("bed").expect("required by clap");
+    let mode = match matches
+        .get_one::<String>("mode")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "soft" => Mode::Soft,
+        _ => Mode::Hard,
+    };
+    let intervals = read_bed(bed)?;
+    // BED chromosomes matching a record, and records
+    // with intervals past their end
+    let mut used = HashSet::new();
+    let mut past_end = Vec::new();
+
+    each_record(matches, |record| {
+        let id = crate::fasta_id_description(record, FID::Id)?;
+        let mut seq = record.sequence().as_ref().to_vec();
+        let n = seq.len();
+        let Some(chrom) = intervals.get(&id) else {
+            return Ok(seq);
+        };
+        used.insert(id.clone());
+        // intervals are sorted, so only the last can run past the end
+        if chrom.last().is_some_and(|(_, end)| *end > n) {
+            past_end.push(id);
+        }
+        for (start, end) in chrom {
+            let region = &mut seq[(*start).min(n)..(*end).min(n)];
+            match mode {
+                Mode::Hard => region.fill(b'N'),
+                Mode::Soft => region.make_ascii_lowercase(),
+            }
+        }
+        Ok(seq)
+    })?;
+
+    let mut unmatched: Vec<&str> = intervals
+        .keys()
+        .filter(|c| !used.contains(*c))
+        .map(String::as_str)
+        .collect();
+    if !unmatched.is_empty() {
+        unmatched.sort_unstable();
+        eprintln!(
+            "[-]\t{} BED chromosome(s) matched no record: {}",
+            unmatched.len(),
+            unmatched.join(", ")
+        );
+    }
+    if !past_end.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) had intervals past their end, which were masked up to the end: {}",
+            past_end.len(),
+            past_end.join(", ")
+        );
+    }
+    Ok(())
+}
+
+pub fn unmask(matches: &clap::ArgMatches) -> Result<()> {
+    each_record(matches, |record| {
+        Ok(record.sequence().as_ref().to_ascii_uppercase())
+    })
+}
+
+pub fn hardmask(matches: &clap::ArgMatches) -> Result<()> {
+    each_record(matches, |record| {
+        Ok(record
+            .sequence()
+            .as_ref()
+            .iter()
+            .map(|base| match base.is_ascii_lowercase() {
+                true => b'N',
+                false => *base,
+            })
+            .collect())
+    })
+}
+
+// stream the records, writing each with a new sequence
+fn each_record(
+    matches: &clap::ArgMatches,
+    mut new_seq: impl FnMut(&Record) -> Result<Vec<u8>>,
+) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut write = |record: &Record| -> Result<()> {
+        let record = Record::new(
+            record.definition().clone(),
+            Sequence::from(new_seq(record)?),
+        );
+        writer
+            .write_record(&record)
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+        Ok(())
+    };
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    write(&record?)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    write(&record)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
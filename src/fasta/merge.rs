@@ -1,89 +1,199 @@
-use crate::utils::{error, stdin};
+use crate::utils::{
+    agp::{self, AgpLine},
+    error, stdin,
+};
 use anyhow::{bail, Result};
+use noodles_fasta::Record;
+use std::fs::File;
+use std::io::{self, prelude::*, BufWriter};
+use std::path::PathBuf;
+
+// builds one merged record, streaming the sequence to stdout
+// unless the header needs the tracking info first
+struct Merger {
+    track: bool,
+    spacer: usize,
+    agp: Option<BufWriter<File>>,
+    out: BufWriter<io::Stdout>,
+    // the merged record being built
+    name: String,
+    len: usize,
+    part_number: usize,
+    tracking_header: Vec<String>,
+    seq: Vec<u8>,
+}
+
+impl Merger {
+    fn start(&mut self, name: &str) -> Result<()> {
+        self.name = name.to_string();
+        self.len = 0;
+        self.part_number = 0;
+        // print header
+        // if we are not using tracking info
+        if !self.track {
+            writeln!(self.out, ">{}", name)?;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, record: &Record) -> Result<()> {
+        let seq = record.sequence().as_ref();
+        let rec_name = std::str::from_utf8(record.name())?;
+
+        // empty records are only tracked, so no gaps surround them
+        if seq.is_empty() {
+            if self.track {
+                self.tracking_header
+                    .push(format!("{},{}-{}", rec_name, self.len + 1, self.len));
+            }
+            return Ok(());
+        }
+        if self.len > 0 && self.spacer > 0 {
+            self.part_number += 1;
+            if let Some(agp) = self.agp.as_mut() {
+                let gap = AgpLine::gap(&self.name, self.len + 1, self.part_number, self.spacer);
+                writeln!(agp, "{}", gap)?;
+            }
+            self.write_seq(&vec![b'N'; self.spacer])?;
+        }
+
+        self.part_number += 1;
+        if let Some(agp) = self.agp.as_mut() {
+            let component = AgpLine::component(
+                &self.name,
+                self.len + 1,
+                self.part_number,
+                rec_name,
+                seq.len(),
+            );
+            writeln!(agp, "{}", component)?;
+        }
+        if self.track {
+            self.tracking_header.push(format!(
+                "{},{}-{}",
+                rec_name,
+                self.len + 1,
+                self.len + seq.len()
+            ));
+        }
+        self.write_seq(seq)
+    }
+
+    fn write_seq(&mut self, seq: &[u8]) -> Result<()> {
+        match self.track {
+            true => self.seq.extend_from_slice(seq),
+            false => self.out.write_all(seq)?,
+        }
+        self.len += seq.len();
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.track {
+            writeln!(self.out, ">{}", self.tracking_header.join(":"))?;
+            self.out.write_all(&self.seq)?;
+            self.tracking_header.clear();
+            self.seq.clear();
+        }
+        writeln!(self.out)?;
+        Ok(())
+    }
+}
+
+// a gap spacer like N100, or just 100
+fn parse_spacer(spacer: &str) -> Result<usize> {
+    let len = spacer
+        .strip_prefix(['N', 'n'])
+        .unwrap_or(spacer)
+        .parse::<usize>();
+    match len {
+        Ok(l) => Ok(l),
+        Err(_) => bail!(
+            "[-]\tCould not parse spacer {}. Use the number of Ns, e.g. N100.",
+            spacer
+        ),
+    }
+}
 
 pub fn merge_fastas(matches: &clap::ArgMatches) -> Result<()> {
     let input_file = crate::get_fasta_files(matches);
     let headers_op = matches.get_one::<String>("header");
-    let track = matches.get_flag("track");
+    let per_file = matches.get_flag("per-file");
+    let spacer = match matches.get_one::<String>("spacer") {
+        Some(s) => parse_spacer(s)?,
+        None => 0,
+    };
 
-    let mut seq = String::new();
-    let mut tracking_header = String::new();
-    let mut cum_seq_len = 1;
+    let mut agp = match matches.get_one::<PathBuf>("agp") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    if let Some(agp) = agp.as_mut() {
+        writeln!(agp, "{}", agp::VERSION)?;
+    }
+    let mut merger = Merger {
+        track: matches.get_flag("track"),
+        spacer,
+        agp,
+        out: BufWriter::new(io::stdout()),
+        name: String::new(),
+        len: 0,
+        part_number: 0,
+        tracking_header: Vec::new(),
+        seq: Vec::new(),
+    };
+    let name = headers_op.map(String::as_str).unwrap_or("merged");
 
     match input_file {
         // read directly from files
         Some(f) => {
-            // print header
-            // if we are not using tracking info
-            if !track {
-                match headers_op {
-                    Some(h) => println!(">{}", h),
-                    None => println!(">merged"),
-                }
+            if !per_file {
+                merger.start(name)?;
             }
             for el in f {
+                if per_file {
+                    let basename = crate::get_basename_from_pathbuf(&el)?;
+                    let stem = basename
+                        .rsplit_once('.')
+                        .map(|(stem, _)| stem)
+                        .unwrap_or(&basename);
+                    merger.start(stem)?;
+                }
                 let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
                 for record in reader.records() {
-                    let record = record?;
-                    // write to stdout
-                    if !track {
-                        print!("{}", std::str::from_utf8(record.sequence().as_ref())?);
-                    } else {
-                        let seq_len = cum_seq_len + record.sequence().len() - 1;
-                        let rec_name = std::str::from_utf8(record.name())?;
-                        tracking_header += &format!("{rec_name},{cum_seq_len}-{seq_len}:");
-
-                        seq += std::str::from_utf8(record.sequence().as_ref())?;
-                        cum_seq_len += record.sequence().len();
-                    }
+                    merger.push(&record?)?;
+                }
+                if per_file {
+                    merger.finish()?;
                 }
             }
-            if !track {
-                println!();
-            } else {
-                // remove last :
-                tracking_header.pop();
-                println!(">{}", tracking_header);
-                println!("{}", seq);
+            if !per_file {
+                merger.finish()?;
             }
         }
         // read from stdin
+        None if per_file => {
+            bail!("[-]\tRecords from stdin are not split by file, so supply the fasta files as arguments with --per-file.")
+        }
         None => match stdin::is_stdin() {
             true => {
-                // print header
-                if !track {
-                    match headers_op {
-                        Some(h) => println!(">{}", h),
-                        None => println!(">merged"),
-                    }
-                }
+                merger.start(name)?;
                 let mut reader = crate::fasta_reader_stdin();
                 let mut records = reader.records();
                 while let Some(Ok(record)) = records.next() {
-                    if !track {
-                        print!("{}", std::str::from_utf8(record.sequence().as_ref())?);
-                    } else {
-                        let seq_len = cum_seq_len + record.sequence().len() - 1;
-                        let rec_name = std::str::from_utf8(record.name())?;
-                        tracking_header += &format!("{rec_name},{cum_seq_len}-{seq_len}:");
-
-                        seq += std::str::from_utf8(record.sequence().as_ref())?;
-                        cum_seq_len += record.sequence().len();
-                    }
-                }
-                if !track {
-                    println!();
-                } else {
-                    // remove last :
-                    tracking_header.pop();
-                    println!(">{}", tracking_header);
-                    println!("{}", seq);
+                    merger.push(&record)?;
                 }
+                merger.finish()?;
             }
             false => {
                 bail!(error::StdinError::NoSequence)
             }
         },
     }
+
+    merger.out.flush()?;
+    if let Some(mut agp) = merger.agp {
+        agp.flush()?;
+    }
     Ok(())
 }
@@ -9,6 +9,7 @@ use std::io;
 
 pub fn min(matches: &clap::ArgMatches) -> Result<()> {
     let input_file = crate::get_fasta_files(matches);
+    let revcomp = !matches.get_flag("no-revcomp");
     let mut writer = fasta::Writer::new(io::stdout());
 
     match input_file {
@@ -17,18 +18,7 @@ pub fn min(matches: &clap::ArgMatches) -> Result<()> {
             for el in f.iter() {
                 let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
                 for record in reader.records() {
-                    let record = record?;
-                    let minimal_rotation = lex_min(record.sequence().as_ref())?;
-
-                    let definition =
-                        Definition::new(record.name(), record.description().map(Into::into));
-                    let out_record = Record::new(
-                        definition,
-                        Sequence::from(minimal_rotation.as_bytes().to_vec()),
-                    );
-                    writer
-                        .write_record(&out_record)
-                        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+                    min_inner(&record?, revcomp, &mut writer)?;
                 }
             }
         }
@@ -38,16 +28,7 @@ pub fn min(matches: &clap::ArgMatches) -> Result<()> {
                 let mut reader = crate::fasta_reader_stdin();
                 let mut records = reader.records();
                 while let Some(Ok(record)) = records.next() {
-                    let minimal_rotation = lex_min(record.sequence().as_ref())?;
-                    let definition =
-                        Definition::new(record.name(), record.description().map(Into::into));
-                    let out_record = Record::new(
-                        definition,
-                        Sequence::from(minimal_rotation.as_bytes().to_vec()),
-                    );
-                    writer
-                        .write_record(&out_record)
-                        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+                    min_inner(&record, revcomp, &mut writer)?;
                 }
             }
             false => {
@@ -57,3 +38,27 @@ pub fn min(matches: &clap::ArgMatches) -> Result<()> {
     }
     Ok(())
 }
+
+fn min_inner(record: &Record, revcomp: bool, writer: &mut fasta::Writer<io::Stdout>) -> Result<()> {
+    let minimal_rotation = lex_min(record.sequence().as_ref(), revcomp);
+    let description = std::str::from_utf8(record.description().unwrap_or_default())?;
+
+    // the strand and (0-based) start of the rotation on that strand
+    let definition = Definition::new(
+        record.name(),
+        Some(
+            format!(
+                "{}:strand={}:offset={}",
+                description,
+                minimal_rotation.strand(),
+                minimal_rotation.offset
+            )
+            .into_bytes(),
+        ),
+    );
+    let out_record = Record::new(definition, Sequence::from(minimal_rotation.seq));
+    writer
+        .write_record(&out_record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
@@ -1,13 +1,32 @@
+pub mod agp;
+pub mod annotate;
+pub mod backtrans;
+pub mod break_gaps;
+pub mod circularise;
+pub mod codon_align;
+pub mod dedup;
+pub mod digest;
+pub mod dnds;
 pub mod extract;
 pub mod filter;
+pub mod gaps;
 pub mod gc;
+pub mod grep_seq;
 pub mod length;
+pub mod mask;
 pub mod merge;
 pub mod min;
 pub mod n50;
 pub mod number;
+pub mod pcr;
+pub mod pdigest;
 pub mod regex;
+pub mod rename;
 pub mod reverse;
+pub mod rotate;
 pub mod sample;
+pub mod search;
+pub mod setop;
 pub mod split;
 pub mod translate;
+pub mod unmerge;
@@ -0,0 +1,258 @@
+// in-silico PCR: find amplicons of primer pairs
+// in template sequences
+
+use crate::{
+    fasta::{extract::region_description, grep_seq::Oligo},
+    utils::{approx, error, revcomp, stdin},
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader, BufWriter};
+use std::path::PathBuf;
+
+struct PrimerPair {
+    name: String,
+    forward: Oligo,
+    reverse: Oligo,
+}
+
+struct Options {
+    mismatches: usize,
+    three_prime: usize,
+    min_size: usize,
+    max_size: usize,
+}
+
+pub fn pcr(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let options = Options {
+        mismatches: *matches
+            .get_one::<usize>("mismatches")
+            .expect("defaulted by clap"),
+        three_prime: *matches
+            .get_one::<usize>("three-prime")
+            .expect("defaulted by clap"),
+        min_size: *matches
+            .get_one::<usize>("min-size")
+            .expect("defaulted by clap"),
+        max_size: *matches
+            .get_one::<usize>("max-size")
+            .expect("defaulted by clap"),
+    };
+
+    let mut pairs = Vec::new();
+    if let (Some(f), Some(r)) = (
+        matches.get_one::<String>("forward"),
+        matches.get_one::<String>("reverse"),
+    ) {
+        pairs.push(PrimerPair {
+            name: "pcr".into(),
+            forward: Oligo::new(f.clone(), f.as_bytes()),
+            reverse: Oligo::new(r.clone(), r.as_bytes()),
+        });
+    }
+    if let Some(path) = matches.get_one::<PathBuf>("primers") {
+        pairs.extend(read_primer_pairs(path)?);
+    }
+    if pairs.is_empty() {
+        bail!("[-]\tNo primer pairs given.");
+    }
+
+    let mut bed = match matches.get_one::<PathBuf>("bed") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    let mut writer = fasta::Writer::new(io::stdout());
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let basename = crate::get_basename_from_pathbuf(el)?;
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                pcr_reader(
+                    &mut reader,
+                    &pairs,
+                    &options,
+                    &basename,
+                    &mut writer,
+                    bed.as_mut(),
+                )?;
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                pcr_reader(
+                    &mut reader,
+                    &pairs,
+                    &options,
+                    "stdin",
+                    &mut writer,
+                    bed.as_mut(),
+                )?;
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if let Some(mut bed) = bed {
+        bed.flush()?;
+    }
+    Ok(())
+}
+
+// a tab separated table of pair name, forward and reverse primer
+fn read_primer_pairs(path: &PathBuf) -> Result<Vec<PrimerPair>> {
+    let file = File::open(path)?;
+    let mut pairs = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').map(|e| e.trim()).collect();
+        if fields.len() < 3 {
+            bail!(
+                "[-]\tPrimer table line {} needs a name, forward and reverse primer.",
+                i + 1
+            );
+        }
+        pairs.push(PrimerPair {
+            name: fields[0].to_string(),
+            forward: Oligo::new(fields[1].to_string(), fields[1].as_bytes()),
+            reverse: Oligo::new(fields[2].to_string(), fields[2].as_bytes()),
+        });
+    }
+    Ok(pairs)
+}
+
+struct Amplicon {
+    start: usize,
+    end: usize,
+    strand: char,
+    mismatches: usize,
+}
+
+fn pcr_reader<R: BufRead>(
+    reader: &mut fasta::io::Reader<R>,
+    pairs: &[PrimerPair],
+    options: &Options,
+    basename: &str,
+    writer: &mut fasta::Writer<io::Stdout>,
+    mut bed: Option<&mut BufWriter<File>>,
+) -> Result<()> {
+    let mut definition = String::new();
+    let mut seq = Vec::new();
+
+    loop {
+        definition.clear();
+        if reader.read_definition(&mut definition)? == 0 {
+            break;
+        }
+        let definition: Definition = definition.parse()?;
+        let id = String::from_utf8(definition.name().to_vec())?;
+        let description = String::from_utf8(definition.description().unwrap_or_default().to_vec())?;
+
+        seq.clear();
+        reader.read_sequence(&mut seq)?;
+
+        for pair in pairs {
+            for (n, amplicon) in amplicons(&seq, pair, options).iter().enumerate() {
+                let name = format!("{}_{}_{}", id, pair.name, n + 1);
+                let amplicon_seq = match amplicon.strand {
+                    '+' => seq[amplicon.start..amplicon.end].to_vec(),
+                    _ => revcomp::reverse_complement(&seq[amplicon.start..amplicon.end]),
+                };
+                let region =
+                    region_description(&description, basename, amplicon.start + 1, amplicon.end);
+                let definition = Definition::new(
+                    name.clone(),
+                    Some(format!("{}:{}", region, amplicon.strand).into_bytes()),
+                );
+                writer
+                    .write_record(&Record::new(definition, Sequence::from(amplicon_seq)))
+                    .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+
+                if let Some(bed) = bed.as_mut() {
+                    writeln!(
+                        bed,
+                        "{}\t{}\t{}\t{}\t{}\t{}",
+                        id,
+                        amplicon.start,
+                        amplicon.end,
+                        name,
+                        amplicon.mismatches,
+                        amplicon.strand
+                    )?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// A primer binding with its 3' end pointing right (the primer itself
+// on the forward strand), or left (its reverse complement).
+fn bind_right(primer: &Oligo, seq: &[u8], options: &Options) -> Vec<approx::Hit> {
+    let m = primer.forward.len();
+    let n = options.three_prime.min(m);
+    approx::hamming(&primer.forward, seq, options.mismatches)
+        .into_iter()
+        .filter(|h| approx::mismatches(&primer.forward[m - n..], &seq[h.end - n..h.end]) == 0)
+        .collect()
+}
+
+fn bind_left(primer: &Oligo, seq: &[u8], options: &Options) -> Vec<approx::Hit> {
+    let n = options.three_prime.min(primer.reverse.len());
+    approx::hamming(&primer.reverse, seq, options.mismatches)
+        .into_iter()
+        .filter(|h| approx::mismatches(&primer.reverse[..n], &seq[h.start..h.start + n]) == 0)
+        .collect()
+}
+
+// products of the forward primer pointing right and the reverse
+// primer pointing left (a + strand amplicon), and vice versa
+fn amplicons(seq: &[u8], pair: &PrimerPair, options: &Options) -> Vec<Amplicon> {
+    let mut amplicons = Vec::new();
+    for (left, right, strand) in [
+        (
+            bind_right(&pair.forward, seq, options),
+            bind_left(&pair.reverse, seq, options),
+            '+',
+        ),
+        (
+            bind_right(&pair.reverse, seq, options),
+            bind_left(&pair.forward, seq, options),
+            '-',
+        ),
+    ] {
+        for l in left.iter() {
+            for r in right.iter() {
+                if r.start < l.start || r.end < l.end {
+                    continue;
+                }
+                let size = r.end - l.start;
+                if size >= options.min_size && size <= options.max_size {
+                    amplicons.push(Amplicon {
+                        start: l.start,
+                        end: r.end,
+                        strand,
+                        mismatches: l.dist + r.dist,
+                    });
+                }
+            }
+        }
+    }
+    amplicons.sort_by_key(|a| (a.start, a.end));
+    amplicons
+}
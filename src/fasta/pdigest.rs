@@ -0,0 +1,254 @@
+// in-silico protease digestion of proteins into peptides
+
+use crate::utils::{error, stdin};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::io;
+
+struct Protease {
+    name: &'static str,
+    // cleaves C-terminal to these residues
+    after: &'static [u8],
+    // cleaves N-terminal to these residues
+    before: &'static [u8],
+    // no cleavage when followed by a proline
+    proline_rule: bool,
+}
+
+static PROTEASES: &[Protease] = &[
+    Protease {
+        name: "trypsin",
+        after: b"KR",
+        before: b"",
+        proline_rule: true,
+    },
+    Protease {
+        name: "trypsin/p",
+        after: b"KR",
+        before: b"",
+        proline_rule: false,
+    },
+    Protease {
+        name: "lys-c",
+        after: b"K",
+        before: b"",
+        proline_rule: true,
+    },
+    Protease {
+        name: "lys-n",
+        after: b"",
+        before: b"K",
+        proline_rule: false,
+    },
+    Protease {
+        name: "arg-c",
+        after: b"R",
+        before: b"",
+        proline_rule: true,
+    },
+    Protease {
+        name: "asp-n",
+        after: b"",
+        before: b"D",
+        proline_rule: false,
+    },
+    Protease {
+        name: "glu-c",
+        after: b"E",
+        before: b"",
+        proline_rule: true,
+    },
+    Protease {
+        name: "chymotrypsin",
+        after: b"FWY",
+        before: b"",
+        proline_rule: true,
+    },
+];
+
+struct Limits {
+    missed: usize,
+    min_len: usize,
+    max_len: Option<usize>,
+    min_mass: Option<f64>,
+    max_mass: Option<f64>,
+}
+
+pub fn protease_digest(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let enzyme = matches
+        .get_one::<String>("enzyme")
+        .expect("defaulted by clap");
+    let protease = match PROTEASES
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(enzyme))
+    {
+        Some(p) => p,
+        None => bail!(
+            "[-]\tUnknown protease {}. Choose from: {}.",
+            enzyme,
+            PROTEASES
+                .iter()
+                .map(|p| p.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let limits = Limits {
+        missed: *matches
+            .get_one::<usize>("missed-cleavages")
+            .expect("defaulted by clap"),
+        min_len: *matches
+            .get_one::<usize>("min-len")
+            .expect("defaulted by clap"),
+        max_len: matches.get_one::<usize>("max-len").cloned(),
+        min_mass: matches.get_one::<f64>("min-mass").cloned(),
+        max_mass: matches.get_one::<f64>("max-mass").cloned(),
+    };
+
+    let mut writer = fasta::Writer::new(io::stdout());
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    pdigest_inner(&record?, protease, &limits, &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    pdigest_inner(&record, protease, &limits, &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
+
+// cleavage positions for each stretch of the protein between
+// stops (e.g. from `mmft trans`), including both ends of the stretch
+fn cleavage_sites(protein: &[u8], protease: &Protease) -> Vec<Vec<usize>> {
+    let mut segments = Vec::new();
+    let mut offset = 0;
+    for segment in protein.split(|aa| *aa == b'*') {
+        if !segment.is_empty() {
+            let mut sites = vec![offset];
+            for i in 1..segment.len() {
+                let prev = segment[i - 1].to_ascii_uppercase();
+                let next = segment[i].to_ascii_uppercase();
+                if (protease.after.contains(&prev) || protease.before.contains(&next))
+                    && !(protease.proline_rule && next == b'P')
+                {
+                    sites.push(offset + i);
+                }
+            }
+            sites.push(offset + segment.len());
+            segments.push(sites);
+        }
+        offset += segment.len() + 1;
+    }
+    segments
+}
+
+fn pdigest_inner(
+    record: &Record,
+    protease: &Protease,
+    limits: &Limits,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let id = String::from_utf8(record.name().to_vec())?;
+    let description = String::from_utf8(record.description().unwrap_or_default().to_vec())?;
+    let protein = record.sequence().as_ref();
+
+    let mut n = 0;
+    for sites in cleavage_sites(protein, protease) {
+        for i in 0..sites.len() {
+            for j in i + 1..sites.len().min(i + limits.missed + 2) {
+                let (start, end) = (sites[i], sites[j]);
+                let peptide = &protein[start..end];
+                let mass = monoisotopic_mass(peptide);
+
+                if peptide.len() < limits.min_len
+                    || limits.max_len.is_some_and(|m| peptide.len() > m)
+                    || limits.min_mass.is_some_and(|m| mass.is_none_or(|e| e < m))
+                    || limits.max_mass.is_some_and(|m| mass.is_none_or(|e| e > m))
+                {
+                    continue;
+                }
+
+                n += 1;
+                let mass = match mass {
+                    Some(m) => format!("{:.4}", m),
+                    None => "NA".into(),
+                };
+                let definition = Definition::new(
+                    format!("{}_{}", id, n),
+                    Some(
+                        format!(
+                            "{}:source={}:pos={}-{}:missed={}:mass={}",
+                            description,
+                            id,
+                            start + 1,
+                            end,
+                            j - i - 1,
+                            mass
+                        )
+                        .into_bytes(),
+                    ),
+                );
+                writer
+                    .write_record(&Record::new(definition, Sequence::from(peptide.to_vec())))
+                    .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// monoisotopic mass of a peptide, or None if it contains
+// residues of unknown mass
+fn monoisotopic_mass(peptide: &[u8]) -> Option<f64> {
+    // water
+    let mut mass = 18.010565;
+    for aa in peptide {
+        mass += match aa.to_ascii_uppercase() {
+            b'G' => 57.021464,
+            b'A' => 71.037114,
+            b'S' => 87.032028,
+            b'P' => 97.052764,
+            b'V' => 99.068414,
+            b'T' => 101.047679,
+            b'C' => 103.009185,
+            b'L' | b'I' => 113.084064,
+            b'N' => 114.042927,
+            b'D' => 115.026943,
+            b'Q' => 128.058578,
+            b'K' => 128.094963,
+            b'E' => 129.042593,
+            b'M' => 131.040485,
+            b'H' => 137.058912,
+            b'F' => 147.068414,
+            b'U' => 150.953636,
+            b'R' => 156.101111,
+            b'Y' => 163.063329,
+            b'W' => 186.079313,
+            b'O' => 237.147727,
+            _ => return None,
+        };
+    }
+    Some(mass)
+}
@@ -1,17 +1,67 @@
-use crate::utils::{error, stdin};
+use crate::{
+    utils::{error, stdin},
+    FID,
+};
 use anyhow::{bail, Result};
-use noodles_fasta as fasta;
-use regex::Regex;
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use regex::{Regex, RegexBuilder};
 use std::io;
+use std::path::PathBuf;
+
+// which part of the header the regex is matched against
+#[derive(Clone, Copy)]
+enum Target {
+    Id,
+    Description,
+    Both,
+}
+
+struct Options {
+    regexes: Vec<Regex>,
+    target: Target,
+    inverse: bool,
+    replace: Option<String>,
+}
 
 pub fn regex_sequences(matches: &clap::ArgMatches) -> Result<()> {
     let input_file = crate::get_fasta_files(matches);
-    let re_str = matches
-        .get_one::<String>("regex")
-        .expect("required by clap");
     let inverse: bool = matches.get_flag("inverse");
+    let ignore_case = matches.get_flag("ignore-case");
+    let target = match matches
+        .get_one::<String>("target")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "id" => Target::Id,
+        "description" => Target::Description,
+        _ => Target::Both,
+    };
+
+    let mut re_strs: Vec<String> = matches
+        .get_many::<String>("regex")
+        .map(|e| e.cloned().collect())
+        .unwrap_or_default();
+    if let Some(path) = matches.get_one::<PathBuf>("patterns-file") {
+        re_strs.extend(
+            std::fs::read_to_string(path)?
+                .lines()
+                .map(|l| l.trim_end_matches('\r'))
+                .filter(|l| !l.is_empty())
+                .map(String::from),
+        );
+    }
+
+    let regexes = re_strs
+        .iter()
+        .map(|re| RegexBuilder::new(re).case_insensitive(ignore_case).build())
+        .collect::<Result<Vec<_>, regex::Error>>()?;
 
-    let re = Regex::new(re_str)?;
+    let options = Options {
+        regexes,
+        target,
+        inverse,
+        replace: matches.get_one::<String>("replace").cloned(),
+    };
 
     // writer here?
     let mut writer = fasta::Writer::new(io::stdout());
@@ -22,24 +72,7 @@ pub fn regex_sequences(matches: &clap::ArgMatches) -> Result<()> {
             for el in f.iter() {
                 let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
                 for record in reader.records() {
-                    let record = record?;
-                    let id_desc = record.definition().to_string();
-
-                    // if there is no match, we want to have
-                    // the option to print
-                    if re.is_match(&id_desc) {
-                        if !inverse {
-                            writer
-                                .write_record(&record)
-                                .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
-                        } else {
-                            continue;
-                        }
-                    } else if inverse {
-                        writer
-                            .write_record(&record)
-                            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
-                    }
+                    regex_inner(&record?, &options, &mut writer)?;
                 }
             }
         }
@@ -49,21 +82,7 @@ pub fn regex_sequences(matches: &clap::ArgMatches) -> Result<()> {
                 let mut reader = crate::fasta_reader_stdin();
                 let mut records = reader.records();
                 while let Some(Ok(record)) = records.next() {
-                    let id_desc = record.definition().to_string();
-
-                    if re.is_match(&id_desc) {
-                        if !inverse {
-                            writer
-                                .write_record(&record)
-                                .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
-                        } else {
-                            continue;
-                        }
-                    } else if inverse {
-                        writer
-                            .write_record(&record)
-                            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
-                    }
+                    regex_inner(&record, &options, &mut writer)?;
                 }
             }
             false => {
@@ -73,3 +92,65 @@ pub fn regex_sequences(matches: &clap::ArgMatches) -> Result<()> {
     }
     Ok(())
 }
+
+fn regex_inner(
+    record: &Record,
+    options: &Options,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let description = crate::fasta_id_description(record, FID::Description)?;
+    let text = match options.target {
+        Target::Id => id.clone(),
+        Target::Description => description.clone(),
+        // the header line as written, including the >
+        Target::Both => record.definition().to_string(),
+    };
+
+    let is_match = options.regexes.iter().any(|re| re.is_match(&text));
+
+    match &options.replace {
+        // rewrite matching headers, and pass the rest through
+        Some(replace) => {
+            if !is_match {
+                return write(record, writer);
+            }
+            let mut text = text;
+            for re in options.regexes.iter() {
+                text = re.replace_all(&text, replace.as_str()).into_owned();
+            }
+            let (id, description) = match options.target {
+                Target::Id => (text, description),
+                Target::Description => (id, text),
+                Target::Both => {
+                    let text = text.strip_prefix('>').unwrap_or(&text);
+                    match text.split_once(char::is_whitespace) {
+                        Some((i, d)) => (i.to_string(), d.to_string()),
+                        None => (text.to_string(), String::new()),
+                    }
+                }
+            };
+            let description = match description.is_empty() {
+                true => None,
+                false => Some(description.into_bytes()),
+            };
+            let record = Record::new(Definition::new(id, description), record.sequence().clone());
+            write(&record, writer)
+        }
+        // if there is no match, we want to have
+        // the option to print
+        None => {
+            if is_match != options.inverse {
+                write(record, writer)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write(record: &Record, writer: &mut fasta::Writer<io::Stdout>) -> Result<()> {
+    writer
+        .write_record(record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
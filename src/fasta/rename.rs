@@ -0,0 +1,314 @@
+// rename records from a template and/or an alias
+// table, optionally writing out the mapping
+
+use crate::{
+    fasta::gc::gc_content,
+    utils::{error, stdin},
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition, Record};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader, BufWriter};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy)]
+enum Field {
+    File,
+    Index,
+    Id,
+    Length,
+    Gc,
+}
+
+// a format spec like 05 (width) or .2 (precision). Numbers are
+// always padded with zeros, as spaces would split the ID
+#[derive(Clone, Copy, Default)]
+struct Spec {
+    width: usize,
+    precision: Option<usize>,
+}
+
+enum Token {
+    Literal(String),
+    Placeholder(Field, Spec),
+}
+
+struct Template(Vec<Token>);
+
+impl Template {
+    // e.g. {file}_{n:05}, with {{ and }} for literal braces
+    fn parse(s: &str) -> Result<Self> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => inner.push(c),
+                            None => bail!("[-]\tUnclosed {{ in template {}.", s),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(Self::placeholder(&inner)?);
+                }
+                '}' => bail!("[-]\tUnmatched }} in template {}.", s),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        Ok(Self(tokens))
+    }
+
+    fn placeholder(inner: &str) -> Result<Token> {
+        let (name, spec) = inner.split_once(':').unwrap_or((inner, ""));
+        let field = match name {
+            "file" => Field::File,
+            "n" => Field::Index,
+            "id" => Field::Id,
+            "len" => Field::Length,
+            "gc" => Field::Gc,
+            _ => bail!(
+                "[-]\tUnknown placeholder {{{}}}. Use {{file}}, {{n}}, {{id}}, {{len}} or {{gc}}.",
+                name
+            ),
+        };
+
+        let mut parsed = Spec::default();
+        let (width, precision) = spec.split_once('.').unwrap_or((spec, ""));
+        if !width.is_empty() {
+            parsed.width = width
+                .parse()
+                .map_err(|_| anyhow::anyhow!("[-]\tCould not parse format spec {}.", spec))?;
+        }
+        if !precision.is_empty() {
+            parsed.precision = Some(
+                precision
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("[-]\tCould not parse format spec {}.", spec))?,
+            );
+        }
+        if matches!(field, Field::File | Field::Id) && !spec.is_empty() {
+            bail!(
+                "[-]\tWidth and precision only apply to {{n}}, {{len}} and {{gc}}, not {{{}}}.",
+                name
+            );
+        }
+        Ok(Token::Placeholder(field, parsed))
+    }
+
+    fn render(&self, file: &str, index: usize, record: &Record) -> Result<String> {
+        let mut out = String::new();
+        for token in self.0.iter() {
+            let (field, spec) = match token {
+                Token::Literal(l) => {
+                    out.push_str(l);
+                    continue;
+                }
+                Token::Placeholder(field, spec) => (field, spec),
+            };
+            let (w, p) = (spec.width, spec.precision);
+            let value = match field {
+                Field::File => file.to_string(),
+                Field::Id => crate::fasta_id_description(record, FID::Id)?,
+                Field::Index => format!("{:0w$}", index),
+                Field::Length => format!("{:0w$}", record.sequence().len()),
+                Field::Gc => {
+                    let gc = gc_content(record.sequence().as_ref());
+                    match p {
+                        Some(p) => format!("{:0w$.p$}", gc),
+                        None => format!("{:0w$}", gc),
+                    }
+                }
+            };
+            out.push_str(&value);
+        }
+        // e.g. a space in the template or file name
+        if out.contains(char::is_whitespace) {
+            bail!(
+                "[-]\tRenamed ID '{}' contains whitespace, so it would be split into an ID and description.",
+                out
+            );
+        }
+        Ok(out)
+    }
+}
+
+struct Options {
+    template: Option<Template>,
+    aliases: HashMap<String, (String, Option<String>)>,
+    keep_description: bool,
+}
+
+pub fn rename(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let template = matches
+        .get_one::<String>("template")
+        .map(|t| Template::parse(t))
+        .transpose()?;
+    let aliases = match matches.get_one::<PathBuf>("alias") {
+        Some(path) => read_aliases(path, matches.get_flag("restore"))?,
+        None => HashMap::new(),
+    };
+    let options = Options {
+        template,
+        aliases,
+        keep_description: matches.get_flag("keep-description"),
+    };
+
+    let mut map = match matches.get_one::<PathBuf>("map") {
+        Some(path) => Some(BufWriter::new(File::create(path)?)),
+        None => None,
+    };
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut seen = HashSet::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let basename = crate::get_basename_from_pathbuf(el)?;
+                let file = basename
+                    .rsplit_once('.')
+                    .map(|(stem, _)| stem)
+                    .unwrap_or(&basename);
+
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for (i, record) in reader.records().enumerate() {
+                    rename_inner(
+                        &record?,
+                        file,
+                        i + 1,
+                        &options,
+                        &mut seen,
+                        &mut writer,
+                        map.as_mut(),
+                    )?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                let mut i = 0;
+                while let Some(Ok(record)) = records.next() {
+                    i += 1;
+                    rename_inner(
+                        &record,
+                        "stdin",
+                        i,
+                        &options,
+                        &mut seen,
+                        &mut writer,
+                        map.as_mut(),
+                    )?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if let Some(mut map) = map {
+        map.flush()?;
+    }
+    Ok(())
+}
+
+// old ID to new ID (and description) from a tab separated table. When
+// restoring, the table is a mapping written by --map, and is read
+// backwards: new ID to old ID and old description.
+fn read_aliases(
+    path: &PathBuf,
+    restore: bool,
+) -> Result<HashMap<String, (String, Option<String>)>> {
+    let file = File::open(path)?;
+    let mut aliases = HashMap::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 2 {
+            bail!(
+                "[-]\tAlias table line {} needs two tab separated IDs.",
+                i + 1
+            );
+        }
+        let (old, new) = (fields[0].trim(), fields[1].trim());
+        match restore {
+            true => aliases.insert(
+                new.to_string(),
+                (
+                    old.to_string(),
+                    fields
+                        .get(2)
+                        .filter(|d| !d.is_empty())
+                        .map(|d| d.to_string()),
+                ),
+            ),
+            false => aliases.insert(old.to_string(), (new.to_string(), None)),
+        };
+    }
+    Ok(aliases)
+}
+
+fn rename_inner(
+    record: &Record,
+    file: &str,
+    index: usize,
+    options: &Options,
+    seen: &mut HashSet<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+    map: Option<&mut BufWriter<File>>,
+) -> Result<()> {
+    let id = crate::fasta_id_description(record, FID::Id)?;
+    let description = crate::fasta_id_description(record, FID::Description)?;
+
+    let (new_id, new_description) = match (options.aliases.get(&id), &options.template) {
+        (Some((alias, restored)), _) => (alias.clone(), restored.clone()),
+        (None, Some(template)) => (template.render(file, index, record)?, None),
+        (None, None) => (id.clone(), None),
+    };
+    let new_description = match new_description {
+        Some(d) => Some(d),
+        None if options.keep_description && !description.is_empty() => Some(description.clone()),
+        None => None,
+    };
+
+    if !seen.insert(new_id.clone()) {
+        eprintln!("[-]\tDuplicate record name {} (from {}).", new_id, id);
+    }
+    if let Some(map) = map {
+        writeln!(map, "{}\t{}\t{}", id, new_id, description)?;
+    }
+
+    let definition = Definition::new(new_id, new_description.map(|d| d.into_bytes()));
+    writer
+        .write_record(&Record::new(definition, record.sequence().clone()))
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
@@ -0,0 +1,149 @@
+// rotate circular records by an offset, or to start
+// at an anchor motif on either strand
+
+use crate::{
+    utils::{
+        approx, error,
+        lex_min::rotate,
+        revcomp::{revcomp_inplace, reverse_complement, Alphabet},
+        stdin,
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::io;
+
+enum Rotation {
+    Offset(i64),
+    Anchor { anchor: Vec<u8>, mismatches: usize },
+}
+
+pub fn rotate_sequences(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let rotation = match (
+        matches.get_one::<i64>("offset"),
+        matches.get_one::<String>("anchor"),
+    ) {
+        (Some(offset), _) => Rotation::Offset(*offset),
+        (None, Some(anchor)) => Rotation::Anchor {
+            anchor: anchor.as_bytes().to_vec(),
+            mismatches: *matches
+                .get_one::<usize>("mismatches")
+                .expect("defaulted by clap"),
+        },
+        (None, None) => bail!("[-]\tSupply either --offset or --anchor."),
+    };
+
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut unanchored = Vec::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    rotate_inner(&record?, &rotation, &mut unanchored, &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    rotate_inner(&record, &rotation, &mut unanchored, &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if !unanchored.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) had no anchor and were not rotated: {}",
+            unanchored.len(),
+            unanchored.join(", ")
+        );
+    }
+    Ok(())
+}
+
+// the best (fewest mismatches, then leftmost) start of the anchor
+// on a circular sequence, including hits across the origin
+fn find_anchor(seq: &[u8], anchor: &[u8], mismatches: usize) -> Option<approx::Hit> {
+    let n = seq.len();
+    let text = [seq, &seq[..anchor.len().saturating_sub(1).min(n)]].concat();
+    approx::hamming(anchor, &text, mismatches)
+        .into_iter()
+        .filter(|h| h.start < n)
+        .min_by_key(|h| (h.dist, h.start))
+}
+
+fn rotate_inner(
+    record: &Record,
+    rotation: &Rotation,
+    unanchored: &mut Vec<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let seq = record.sequence().as_ref();
+    let n = seq.len() as i64;
+
+    // (reverse complement first, offset on that strand)
+    let placement = match rotation {
+        _ if n == 0 => None,
+        Rotation::Offset(offset) => Some((false, offset.rem_euclid(n) as usize)),
+        Rotation::Anchor { anchor, mismatches } => {
+            let forward = find_anchor(seq, anchor, *mismatches);
+            let reverse = find_anchor(&reverse_complement(seq), anchor, *mismatches);
+            match (forward, reverse) {
+                (Some(f), Some(r)) if r.dist < f.dist => Some((true, r.start)),
+                (Some(f), _) => Some((false, f.start)),
+                (None, Some(r)) => Some((true, r.start)),
+                (None, None) => None,
+            }
+        }
+    };
+
+    let record = match placement {
+        Some((reverse, offset)) => {
+            let mut seq = seq.to_vec();
+            if reverse {
+                revcomp_inplace(&mut seq, Alphabet::Dna);
+            }
+            let description = crate::fasta_id_description(record, FID::Description)?;
+            let definition = Definition::new(
+                record.name(),
+                Some(
+                    format!(
+                        "{}:strand={}:offset={}",
+                        description,
+                        if reverse { '-' } else { '+' },
+                        offset
+                    )
+                    .into_bytes(),
+                ),
+            );
+            Record::new(definition, Sequence::from(rotate(&seq, offset)))
+        }
+        None => {
+            if matches!(rotation, Rotation::Anchor { .. }) {
+                unanchored.push(crate::fasta_id_description(record, FID::Id)?);
+            }
+            record.clone()
+        }
+    };
+
+    writer
+        .write_record(&record)
+        .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    Ok(())
+}
@@ -33,13 +33,13 @@ pub fn sample(matches: &clap::ArgMatches) -> Result<()> {
                     let mut remaining_to_sample = sn;
                     let mut remaining_in_file = total_records;
 
-                    let mut rng = rand::thread_rng();
+                    let mut rng = rand::rng();
 
                     for result in reader.records() {
                         let record = result?;
 
                         let prob = remaining_to_sample as f64 / remaining_in_file as f64;
-                        if rng.gen_bool(prob) {
+                        if rng.random_bool(prob) {
                             // Write the record if selected
                             writer.write_record(&record)?;
                             remaining_to_sample -= 1;
@@ -63,7 +63,7 @@ pub fn sample(matches: &clap::ArgMatches) -> Result<()> {
                     }
 
                     let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
-                    let mut rng = rand::thread_rng();
+                    let mut rng = rand::rng();
                     let mut total_bytes_written: usize = 0;
                     let mut remaining_records = total_records;
 
@@ -81,7 +81,7 @@ pub fn sample(matches: &clap::ArgMatches) -> Result<()> {
                             / (record_bytes as f64 * remaining_records as f64);
 
                         // Randomly decide whether to include this record
-                        if rng.gen_bool(prob.clamp(0.0, 1.0)) {
+                        if rng.random_bool(prob.clamp(0.0, 1.0)) {
                             if total_bytes_written + record_bytes > ss {
                                 break; // Stop if adding this record would exceed the byte limit
                             }
@@ -0,0 +1,134 @@
+// search sequences for a regex or IUPAC motif on
+// both strands, reporting hits as BED
+
+use crate::utils::{
+    error, iupac,
+    revcomp::{self, Alphabet},
+    stdin,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{self as fasta, record::Definition};
+use regex::bytes::{Regex, RegexBuilder};
+use std::io::BufRead;
+
+struct Hit {
+    start: usize,
+    end: usize,
+    strand: char,
+    matched: String,
+}
+
+pub fn search(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+    let pattern = matches
+        .get_one::<String>("pattern")
+        .expect("required by clap");
+    let overlap = matches.get_flag("overlap");
+
+    let re_str = match matches.get_flag("iupac") {
+        true => iupac::motif_to_regex(pattern),
+        false => pattern.clone(),
+    };
+    let re = RegexBuilder::new(&re_str)
+        .case_insensitive(true)
+        .unicode(false)
+        .build()
+        .map_err(|_| error::RegexError::CouldNotCompile)?;
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                search_reader(&mut reader, &re, pattern, overlap)?;
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                search_reader(&mut reader, &re, pattern, overlap)?;
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+    Ok(())
+}
+
+// records are read one at a time into a reused buffer, which is
+// reverse complemented in place to search the reverse strand
+fn search_reader<R: BufRead>(
+    reader: &mut fasta::io::Reader<R>,
+    re: &Regex,
+    pattern: &str,
+    overlap: bool,
+) -> Result<()> {
+    let mut definition = String::new();
+    let mut seq = Vec::new();
+
+    loop {
+        definition.clear();
+        if reader.read_definition(&mut definition)? == 0 {
+            break;
+        }
+        let definition: Definition = definition.parse()?;
+        let id = String::from_utf8(definition.name().to_vec())?;
+
+        seq.clear();
+        reader.read_sequence(&mut seq)?;
+        let len = seq.len();
+
+        let mut hits: Vec<Hit> = find_all(re, &seq, overlap)
+            .map(|(start, end)| Hit {
+                start,
+                end,
+                strand: '+',
+                matched: String::from_utf8_lossy(&seq[start..end]).into(),
+            })
+            .collect();
+
+        revcomp::revcomp_inplace(&mut seq, Alphabet::Dna);
+        // coordinates on the reverse strand are flipped back
+        // onto the forward strand
+        hits.extend(find_all(re, &seq, overlap).map(|(start, end)| Hit {
+            start: len - end,
+            end: len - start,
+            strand: '-',
+            matched: String::from_utf8_lossy(&seq[start..end]).into(),
+        }));
+
+        hits.sort_by_key(|h| (h.start, h.end));
+        for hit in hits {
+            println!(
+                "{}\t{}\t{}\t{}\t0\t{}\t{}",
+                id, hit.start, hit.end, pattern, hit.strand, hit.matched
+            );
+        }
+    }
+    Ok(())
+}
+
+// (start, end) of every non-empty match, optionally
+// restarting the search one base after each match start
+fn find_all<'a>(
+    re: &'a Regex,
+    seq: &'a [u8],
+    overlap: bool,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < seq.len() {
+            let m = re.find_at(seq, pos)?;
+            pos = match overlap {
+                true => m.start() + 1,
+                false => m.end().max(m.start() + 1),
+            };
+            if m.end() > m.start() {
+                return Some((m.start(), m.end()));
+            }
+        }
+        None
+    })
+}
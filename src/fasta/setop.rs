@@ -0,0 +1,96 @@
+// set operations on the records of two fasta files,
+// compared by ID, sequence or canonical sequence
+
+use crate::utils::{
+    error,
+    key::{record_key, KeyBy},
+};
+use anyhow::Result;
+use noodles_fasta as fasta;
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    Union,
+    Intersect,
+    Diff,
+    Symdiff,
+}
+
+pub fn setop(matches: &clap::ArgMatches) -> Result<()> {
+    let operation = match matches
+        .get_one::<String>("operation")
+        .expect("required by clap")
+        .as_str()
+    {
+        "union" => Operation::Union,
+        "intersect" => Operation::Intersect,
+        "diff" => Operation::Diff,
+        _ => Operation::Symdiff,
+    };
+    let a = matches.get_one::<PathBuf>("a").expect("required by clap");
+    let b = matches.get_one::<PathBuf>("b").expect("required by clap");
+    let by = KeyBy::new(matches.get_one::<String>("by").expect("defaulted by clap"));
+
+    let keys_a = read_keys(a, by)?;
+    let keys_b = read_keys(b, by)?;
+    let shared = keys_a.intersection(&keys_b).count();
+
+    let mut writer = fasta::Writer::new(io::stdout());
+    // keys already written, so each is only written once
+    let mut written = HashSet::new();
+
+    // records of a, then those of b not in a
+    let keep_a = |key: &u64| match operation {
+        Operation::Union => true,
+        Operation::Intersect => keys_b.contains(key),
+        Operation::Diff | Operation::Symdiff => !keys_b.contains(key),
+    };
+    write_records(a, by, keep_a, &mut written, &mut writer)?;
+    if matches!(operation, Operation::Union | Operation::Symdiff) {
+        let keep_b = |key: &u64| !keys_a.contains(key);
+        write_records(b, by, keep_b, &mut written, &mut writer)?;
+    }
+
+    eprintln!(
+        "[-]\t{}: {} unique, {}: {} unique, {} shared, {} written.",
+        a.display(),
+        keys_a.len(),
+        b.display(),
+        keys_b.len(),
+        shared,
+        written.len()
+    );
+    Ok(())
+}
+
+fn read_keys(path: &Path, by: KeyBy) -> Result<HashSet<u64>> {
+    let mut reader = crate::fasta_reader_file(path.to_path_buf())?;
+    let mut keys = HashSet::new();
+    for record in reader.records() {
+        keys.insert(record_key(&record?, by)?);
+    }
+    Ok(keys)
+}
+
+fn write_records(
+    path: &Path,
+    by: KeyBy,
+    keep: impl Fn(&u64) -> bool,
+    written: &mut HashSet<u64>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let mut reader = crate::fasta_reader_file(path.to_path_buf())?;
+    for record in reader.records() {
+        let record = record?;
+        let key = record_key(&record, by)?;
+        if keep(&key) && written.insert(key) {
+            writer
+                .write_record(&record)
+                .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+// split merged records back into the originals, from the
+// header written by `merge --track`, or from an AGP file
+
+use crate::{
+    utils::{
+        agp::{read_agp, AgpLine, Part},
+        error,
+        revcomp::reverse_complement,
+        stdin,
+    },
+    FID,
+};
+use anyhow::{bail, Result};
+use noodles_fasta::{
+    self as fasta,
+    record::{Definition, Sequence},
+    Record,
+};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+// a component of a merged record, 1-based and closed
+struct Component {
+    name: String,
+    start: usize,
+    end: usize,
+    reverse: bool,
+}
+
+pub fn unmerge(matches: &clap::ArgMatches) -> Result<()> {
+    let input_file = crate::get_fasta_files(matches);
+
+    // object name to its components
+    let agp = match matches.get_one::<PathBuf>("agp") {
+        Some(path) => Some(agp_components(read_agp(path)?)),
+        None => None,
+    };
+    let mut writer = fasta::Writer::new(io::stdout());
+    let mut unknown = Vec::new();
+
+    match input_file {
+        // read directly from files
+        Some(f) => {
+            for el in f.iter() {
+                let mut reader = crate::fasta_reader_file(el.to_path_buf())?;
+                for record in reader.records() {
+                    unmerge_inner(&record?, agp.as_ref(), &mut unknown, &mut writer)?;
+                }
+            }
+        }
+        // read from stdin
+        None => match stdin::is_stdin() {
+            true => {
+                let mut reader = crate::fasta_reader_stdin();
+                let mut records = reader.records();
+                while let Some(Ok(record)) = records.next() {
+                    unmerge_inner(&record, agp.as_ref(), &mut unknown, &mut writer)?;
+                }
+            }
+            false => {
+                bail!(error::StdinError::NoSequence);
+            }
+        },
+    }
+
+    if !unknown.is_empty() {
+        eprintln!(
+            "[-]\t{} record(s) were not in the AGP: {}",
+            unknown.len(),
+            unknown.join(", ")
+        );
+    }
+    Ok(())
+}
+
+fn agp_components(lines: Vec<AgpLine>) -> HashMap<String, Vec<Component>> {
+    let mut objects: HashMap<String, Vec<Component>> = HashMap::new();
+    for line in lines {
+        let reverse = line.is_reverse();
+        if let Part::Component { id, .. } = line.part {
+            objects.entry(line.object).or_default().push(Component {
+                name: id,
+                start: line.object_beg,
+                end: line.object_end,
+                reverse,
+            });
+        }
+    }
+    objects
+}
+
+// a header like a,1-100:b,101-250, where names may
+// themselves contain a colon
+fn parse_track(header: &str) -> Result<Vec<Component>> {
+    let mut components = Vec::new();
+    let mut name_prefix = String::new();
+    for entry in header.split(':') {
+        let entry = match name_prefix.is_empty() {
+            true => entry.to_string(),
+            false => format!("{}:{}", name_prefix, entry),
+        };
+        let parsed = entry.rsplit_once(',').and_then(|(name, range)| {
+            let (start, end) = range.split_once('-')?;
+            Some(Component {
+                name: name.to_string(),
+                start: start.parse().ok()?,
+                end: end.parse().ok()?,
+                reverse: false,
+            })
+        });
+        match parsed {
+            Some(c) => {
+                components.push(c);
+                name_prefix.clear();
+            }
+            None => name_prefix = entry,
+        }
+    }
+    if !name_prefix.is_empty() || components.is_empty() {
+        bail!(
+            "[-]\tCould not parse {} as a merge --track header (name,start-end:...).",
+            header
+        );
+    }
+    Ok(components)
+}
+
+fn unmerge_inner(
+    record: &Record,
+    agp: Option<&HashMap<String, Vec<Component>>>,
+    unknown: &mut Vec<String>,
+    writer: &mut fasta::Writer<io::Stdout>,
+) -> Result<()> {
+    let seq = record.sequence().as_ref();
+    let parsed;
+    let components = match agp {
+        Some(objects) => {
+            let id = crate::fasta_id_description(record, FID::Id)?;
+            match objects.get(&id) {
+                Some(c) => c,
+                None => {
+                    unknown.push(id);
+                    return Ok(());
+                }
+            }
+        }
+        None => {
+            let header = match record.description() {
+                Some(_) => crate::fasta_id_description(record, FID::Both(" ".into()))?,
+                None => crate::fasta_id_description(record, FID::Id)?,
+            };
+            parsed = parse_track(&header)?;
+            &parsed
+        }
+    };
+
+    for c in components {
+        // empty records are tracked as e.g. a,11-10
+        if c.end > seq.len() || c.start == 0 || c.start > c.end + 1 {
+            bail!(
+                "[-]\t{} at {}-{} is outside the merged record of length {}.",
+                c.name,
+                c.start,
+                c.end,
+                seq.len()
+            );
+        }
+        let part = &seq[c.start - 1..c.end];
+        let part = match c.reverse {
+            true => reverse_complement(part),
+            false => part.to_vec(),
+        };
+        let definition = Definition::new(c.name.clone(), None);
+        writer
+            .write_record(&Record::new(definition, Sequence::from(part)))
+            .map_err(|_| error::FastaWriteError::CouldNotWrite)?;
+    }
+    Ok(())
+}
@@ -28,6 +28,7 @@ pub(crate) fn fasta_reader_stdin() -> Reader<BufReader<std::io::Stdin>> {
     Reader::new(BufReader::new(std::io::stdin()))
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub(crate) enum FID {
     Id,
     Description,
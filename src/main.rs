@@ -4,19 +4,38 @@ use clap::{value_parser, ArgAction};
 use std::path::PathBuf;
 use std::process;
 
+use mmft::fasta::agp;
+use mmft::fasta::annotate;
+use mmft::fasta::backtrans;
+use mmft::fasta::break_gaps;
+use mmft::fasta::circularise;
+use mmft::fasta::codon_align;
+use mmft::fasta::dedup;
+use mmft::fasta::digest;
+use mmft::fasta::dnds;
 use mmft::fasta::extract;
 use mmft::fasta::filter;
+use mmft::fasta::gaps;
 use mmft::fasta::gc;
+use mmft::fasta::grep_seq;
 use mmft::fasta::length;
+use mmft::fasta::mask;
 use mmft::fasta::merge;
 use mmft::fasta::min;
 use mmft::fasta::n50;
 use mmft::fasta::number;
+use mmft::fasta::pcr;
+use mmft::fasta::pdigest;
 use mmft::fasta::regex;
+use mmft::fasta::rename;
 use mmft::fasta::reverse;
+use mmft::fasta::rotate;
 use mmft::fasta::sample;
+use mmft::fasta::search;
+use mmft::fasta::setop;
 use mmft::fasta::split;
 use mmft::fasta::translate;
+use mmft::fasta::unmerge;
 
 fn main() -> Result<()> {
     let matches = Command::new("mmft")
@@ -86,10 +105,34 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new("regex")
                         .short('r')
+                        .short_alias('e')
                         .long("regex")
                         .num_args(1)
-                        .required(true)
-                        .help("Regex to compile."),
+                        .action(ArgAction::Append)
+                        .required_unless_present("patterns-file")
+                        .help("Regex to compile. Can be given multiple times, and a record matches if any regex matches."),
+                )
+                .arg(
+                    Arg::new("patterns-file")
+                        .long("patterns-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("File of regexes, one per line."),
+                )
+                .arg(
+                    Arg::new("target")
+                        .short('t')
+                        .long("target")
+                        .value_parser(["id", "description", "both"])
+                        .num_args(1)
+                        .default_value("both")
+                        .help("Match the regex against the record ID, description, or both (the whole header line, starting with >)."),
+                )
+                .arg(
+                    Arg::new("ignore-case")
+                        .long("ignore-case")
+                        .action(ArgAction::SetTrue)
+                        .help("Case-insensitive regex match."),
                 )
                 .arg(
                     Arg::new("inverse")
@@ -97,6 +140,209 @@ fn main() -> Result<()> {
                         .long("inverse")
                         .action(ArgAction::SetTrue)
                         .help("Inverse regex match."),
+                )
+                .arg(
+                    Arg::new("replace")
+                        .long("replace")
+                        .num_args(1)
+                        .conflicts_with("inverse")
+                        .help("Rewrite the matched target, with capture groups as $1, $name etc. All records are printed, those not matching unchanged."),
+                ),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("Search sequences on both strands for a regex or IUPAC motif, reporting hits as BED.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("pattern")
+                        .short('p')
+                        .long("pattern")
+                        .num_args(1)
+                        .required(true)
+                        .help("Regex (or IUPAC motif with --iupac) to search for. Matching is case-insensitive."),
+                )
+                .arg(
+                    Arg::new("iupac")
+                        .short('u')
+                        .long("iupac")
+                        .action(ArgAction::SetTrue)
+                        .help("Treat the pattern as an IUPAC nucleotide motif."),
+                )
+                .arg(
+                    Arg::new("overlap")
+                        .short('o')
+                        .long("overlap")
+                        .action(ArgAction::SetTrue)
+                        .help("Report overlapping matches."),
+                ),
+        )
+        .subcommand(
+            Command::new("grep-seq")
+                .about("Find oligos in sequences on both strands, allowing mismatches, indels and IUPAC degeneracy.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("pattern")
+                        .short('p')
+                        .long("pattern")
+                        .num_args(1)
+                        .action(ArgAction::Append)
+                        .required_unless_present("oligos")
+                        .help("Oligo sequence to search for. Can be given multiple times."),
+                )
+                .arg(
+                    Arg::new("oligos")
+                        .long("oligos")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Fasta file of oligos to search for."),
+                )
+                .arg(
+                    Arg::new("mismatches")
+                        .short('m')
+                        .long("mismatches")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Maximum number of mismatches (or edits with --indels)."),
+                )
+                .arg(
+                    Arg::new("indels")
+                        .long("indels")
+                        .action(ArgAction::SetTrue)
+                        .help("Allow insertions and deletions as well as mismatches."),
+                ),
+        )
+        .subcommand(
+            Command::new("pcr")
+                .about("In-silico PCR. Print the amplicons of primer pairs in template sequences.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input template fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("forward")
+                        .short('f')
+                        .long("forward")
+                        .num_args(1)
+                        .requires("reverse")
+                        .required_unless_present("primers")
+                        .help("Forward primer sequence."),
+                )
+                .arg(
+                    Arg::new("reverse")
+                        .short('r')
+                        .long("reverse")
+                        .num_args(1)
+                        .requires("forward")
+                        .help("Reverse primer sequence."),
+                )
+                .arg(
+                    Arg::new("primers")
+                        .short('p')
+                        .long("primers")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Tab separated file of primer pair name, forward and reverse primer."),
+                )
+                .arg(
+                    Arg::new("mismatches")
+                        .short('m')
+                        .long("mismatches")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Maximum number of mismatches in each primer."),
+                )
+                .arg(
+                    Arg::new("three-prime")
+                        .long("three-prime")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("3")
+                        .help("Number of bases at the 3' end of each primer which must match exactly."),
+                )
+                .arg(
+                    Arg::new("min-size")
+                        .long("min-size")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Minimum amplicon size."),
+                )
+                .arg(
+                    Arg::new("max-size")
+                        .long("max-size")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("5000")
+                        .help("Maximum amplicon size."),
+                )
+                .arg(
+                    Arg::new("bed")
+                        .long("bed")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Write amplicon coordinates to this BED file."),
+                ),
+        )
+        .subcommand(
+            Command::new("rename")
+                .about("Rename fasta records from a template and/or an alias table.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("template")
+                        .short('t')
+                        .long("template")
+                        .num_args(1)
+                        .required_unless_present("alias")
+                        .help("Template for new IDs, e.g. '{file}_{n:05}'. Placeholders are {file} (file name without extension), {n} (index of the record in its file), {id}, {len} and {gc}. Numbers take an optional zero padded width and precision, e.g. {n:4} or {gc:.2}."),
+                )
+                .arg(
+                    Arg::new("alias")
+                        .short('a')
+                        .long("alias")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Tab separated table of old and new IDs. These take precedence over the template."),
+                )
+                .arg(
+                    Arg::new("restore")
+                        .long("restore")
+                        .action(ArgAction::SetTrue)
+                        .requires("alias")
+                        .help("Read the alias table as a mapping written by --map, restoring the original IDs and descriptions."),
+                )
+                .arg(
+                    Arg::new("map")
+                        .short('m')
+                        .long("map")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Write a table of old ID, new ID and old description to this file."),
+                )
+                .arg(
+                    Arg::new("keep-description")
+                        .short('k')
+                        .long("keep-description")
+                        .action(ArgAction::SetTrue)
+                        .help("Keep record descriptions. By default only the new ID is written."),
                 ),
         )
         .subcommand(
@@ -118,6 +364,95 @@ fn main() -> Result<()> {
                         .help("Numeric region to extract."),
                 ),
         )
+        .subcommand(
+            Command::new("digest")
+                .about("Simulate a restriction digest, printing the fragments of each record.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("enzyme")
+                        .short('e')
+                        .long("enzyme")
+                        .num_args(1)
+                        .required(true)
+                        .help("Comma separated enzyme names (e.g. EcoRI,BamHI), or sites with a ^ at the top strand cut (e.g. G^AATTC)."),
+                )
+                .arg(
+                    Arg::new("circular")
+                        .short('c')
+                        .long("circular")
+                        .action(ArgAction::SetTrue)
+                        .help("Treat records as circular molecules."),
+                )
+                .arg(
+                    Arg::new("table")
+                        .short('t')
+                        .long("table")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Write a table of fragment coordinates, sizes and the enzymes at each end to this file."),
+                ),
+        )
+        .subcommand(
+            Command::new("pdigest")
+                .about("Digest protein records into peptides with a protease.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input protein fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("enzyme")
+                        .short('e')
+                        .long("enzyme")
+                        .num_args(1)
+                        .default_value("trypsin")
+                        .help("Protease: trypsin, trypsin/p, lys-c, lys-n, arg-c, asp-n, glu-c or chymotrypsin."),
+                )
+                .arg(
+                    Arg::new("missed-cleavages")
+                        .short('m')
+                        .long("missed-cleavages")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Maximum number of missed cleavages in a peptide."),
+                )
+                .arg(
+                    Arg::new("min-len")
+                        .long("min-len")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("1")
+                        .help("Minimum peptide length."),
+                )
+                .arg(
+                    Arg::new("max-len")
+                        .long("max-len")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .help("Maximum peptide length."),
+                )
+                .arg(
+                    Arg::new("min-mass")
+                        .long("min-mass")
+                        .value_parser(value_parser!(f64))
+                        .num_args(1)
+                        .help("Minimum monoisotopic peptide mass (Da)."),
+                )
+                .arg(
+                    Arg::new("max-mass")
+                        .long("max-mass")
+                        .value_parser(value_parser!(f64))
+                        .num_args(1)
+                        .help("Maximum monoisotopic peptide mass (Da)."),
+                ),
+        )
         .subcommand(
             Command::new("num")
                 .about("Calculate number and total base count of fasta file records.")
@@ -153,6 +488,194 @@ fn main() -> Result<()> {
                         .long("header")
                         .num_args(1)
                         .help("Name of output fasta header."),
+                )
+                .arg(
+                    Arg::new("spacer")
+                        .short('s')
+                        .long("spacer")
+                        .num_args(1)
+                        .help("Gap of Ns between merged records, e.g. N100."),
+                )
+                .arg(
+                    Arg::new("agp")
+                        .long("agp")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .conflicts_with("track")
+                        .help("Write an AGP 2.1 file describing the records and gaps in the merged sequence(s). The AGP replaces the --track header for unmerge."),
+                )
+                .arg(
+                    Arg::new("per-file")
+                        .long("per-file")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("header")
+                        .help("Merge the records of each file into a record named after the file. Needs the fasta files as arguments, not stdin."),
+                ),
+        )
+        .subcommand(
+            Command::new("unmerge")
+                .about("Split merged records back into the original records.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("agp")
+                        .long("agp")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Split records using the components of an AGP file, rather than the merge --track header."),
+                ),
+        )
+        .subcommand(
+            Command::new("gaps")
+                .about("Print BED intervals of runs of N.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("min-len")
+                        .short('l')
+                        .long("min-len")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("1")
+                        .help("Minimum length of a run of N."),
+                )
+                .arg(
+                    Arg::new("summary")
+                        .short('s')
+                        .long("summary")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the count, total length and fraction of each record instead."),
+                ),
+        )
+        .subcommand(
+            Command::new("masked")
+                .about("Print BED intervals of soft-masked (lowercase) bases.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("min-len")
+                        .short('l')
+                        .long("min-len")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("1")
+                        .help("Minimum length of a run of lowercase bases."),
+                )
+                .arg(
+                    Arg::new("summary")
+                        .short('s')
+                        .long("summary")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the count, total length and fraction of each record instead."),
+                ),
+        )
+        .subcommand(
+            Command::new("mask")
+                .about("Mask the intervals of a BED file with N, or lowercase.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("bed")
+                        .short('b')
+                        .long("bed")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("BED file of the intervals to mask."),
+                )
+                .arg(
+                    Arg::new("mode")
+                        .short('m')
+                        .long("mode")
+                        .value_parser(["hard", "soft"])
+                        .num_args(1)
+                        .default_value("hard")
+                        .help("Hard mask (N) or soft mask (lowercase)."),
+                ),
+        )
+        .subcommand(
+            Command::new("unmask")
+                .about("Uppercase all bases, removing soft-masking.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                ),
+        )
+        .subcommand(
+            Command::new("hardmask")
+                .about("Replace soft-masked (lowercase) bases with N.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                ),
+        )
+        .subcommand(
+            Command::new("break")
+                .about("Break scaffolds into contigs at runs of N.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("min-gap")
+                        .short('g')
+                        .long("min-gap")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("10")
+                        .help("Minimum length of a run of N to break at."),
+                )
+                .arg(
+                    Arg::new("agp")
+                        .long("agp")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Write an AGP 2.1 file describing the scaffolds in terms of the contigs. Runs of N at the ends of scaffolds are left out."),
+                ),
+        )
+        .subcommand(
+            Command::new("agp")
+                .about("Work with AGP files.")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("build")
+                        .about("Build object (e.g. scaffold) sequences from components and an AGP file.")
+                        .arg(
+                            Arg::new("contigs")
+                                .value_parser(value_parser!(PathBuf))
+                                .num_args(1)
+                                .required(true)
+                                .help("Fasta file path of the components. Read through its .fai index if there is one, otherwise loaded into memory."),
+                        )
+                        .arg(
+                            Arg::new("agp")
+                                .value_parser(value_parser!(PathBuf))
+                                .num_args(1)
+                                .required(true)
+                                .help("AGP file path."),
+                        ),
                 ),
         )
         .subcommand(
@@ -166,6 +689,150 @@ fn main() -> Result<()> {
                         .help("Input fasta file path(s)."),
                 ),
         )
+        .subcommand(
+            Command::new("backtrans")
+                .about("Reverse translate protein records into (degenerate) DNA.")
+                // output file name
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("codon-usage")
+                        .short('c')
+                        .long("codon-usage")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Table of codon and usage per line. The most used codon for each amino acid is chosen instead of the IUPAC degenerate codon."),
+                ),
+        )
+        .subcommand(
+            Command::new("codon-align")
+                .about("Thread coding sequences through a protein alignment to make a codon alignment.")
+                .arg(
+                    Arg::new("protein")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("Aligned protein fasta file path."),
+                )
+                .arg(
+                    Arg::new("cds")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("Unaligned coding sequence fasta file path. Records are matched to the protein alignment by ID."),
+                ),
+        )
+        .subcommand(
+            Command::new("setop")
+                .about("Union, intersection or difference of the records of two fasta files.")
+                .arg(
+                    Arg::new("operation")
+                        .value_parser(["union", "intersect", "diff", "symdiff"])
+                        .num_args(1)
+                        .required(true)
+                        .help("The set operation. diff gives records of the first file not in the second."),
+                )
+                .arg(
+                    Arg::new("a")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("First fasta file path."),
+                )
+                .arg(
+                    Arg::new("b")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("Second fasta file path."),
+                )
+                .arg(
+                    Arg::new("by")
+                        .short('b')
+                        .long("by")
+                        .value_parser(["id", "seq", "canonical"])
+                        .num_args(1)
+                        .default_value("id")
+                        .help("Compare records by ID, sequence, or canonical sequence (minimal rotation over both strands)."),
+                ),
+        )
+        .subcommand(
+            Command::new("dedup")
+                .about("Remove duplicate records, keeping the first.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("by")
+                        .short('b')
+                        .long("by")
+                        .value_parser(["id", "seq", "canonical"])
+                        .num_args(1)
+                        .default_value("seq")
+                        .help("Compare records by ID, sequence, or canonical sequence (minimal rotation over both strands)."),
+                )
+                .arg(
+                    Arg::new("merged")
+                        .short('m')
+                        .long("merged")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Write a tab separated table of kept and removed IDs."),
+                ),
+        )
+        .subcommand(
+            Command::new("dnds")
+                .about("Pairwise synonymous and non-synonymous substitution rates from an in-frame codon alignment.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input codon alignment fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("method")
+                        .short('m')
+                        .long("method")
+                        .value_parser(["ng", "mng"])
+                        .num_args(1)
+                        .default_value("ng")
+                        .help("Nei-Gojobori with a Jukes-Cantor correction (ng), or modified Nei-Gojobori, with sites weighted by the transition/transversion ratio and a Kimura two parameter correction (mng)."),
+                ),
+        )
+        .subcommand(
+            Command::new("annotate")
+                .about("Add columns of a metadata table to record descriptions as key=value pairs.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("table")
+                        .short('t')
+                        .long("table")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .required(true)
+                        .help("Tab separated metadata table, with a header line naming the columns."),
+                )
+                .arg(
+                    Arg::new("key")
+                        .short('k')
+                        .long("key")
+                        .num_args(1)
+                        .default_value("id")
+                        .help("Name of the column holding record IDs."),
+                ),
+        )
         .subcommand(
             Command::new("filter")
                 .about("Filter sequences on a file of ID's")
@@ -181,8 +848,61 @@ fn main() -> Result<()> {
                         .short('f')
                         .long("file")
                         .num_args(1)
-                        .required(true)
-                        .help("Name of text file with one ID per line."),
+                        .required_unless_present("ids-from")
+                        .conflicts_with("ids-from")
+                        .help("Name of text file with one ID per line, or - to read the IDs from stdin."),
+                )
+                .arg(
+                    Arg::new("column")
+                        .short('c')
+                        .long("column")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .requires("file")
+                        .help("Take the IDs from this (1-based) column of a tab separated file, such as a TSV or BED."),
+                )
+                .arg(
+                    Arg::new("ids-from")
+                        .long("ids-from")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1)
+                        .help("Take the IDs from the headers of another fasta file."),
+                )
+                .arg(
+                    Arg::new("keep-order")
+                        .long("keep-order")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("inverse")
+                        .help("Write records in the order of the ID list, rather than the fasta. Holds the records in memory."),
+                )
+                .arg(
+                    Arg::new("match")
+                        .short('m')
+                        .long("match")
+                        .value_parser(["id", "description", "full"])
+                        .num_args(1)
+                        .default_value("id")
+                        .help("Match the IDs against the record ID, description, or full header (ID and description separated by a space)."),
+                )
+                .arg(
+                    Arg::new("prefix")
+                        .long("prefix")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("glob")
+                        .help("Keep records where an ID is a prefix of the header."),
+                )
+                .arg(
+                    Arg::new("glob")
+                        .long("glob")
+                        .action(ArgAction::SetTrue)
+                        .help("Treat the IDs as glob patterns (*, ? and [...])."),
+                )
+                .arg(
+                    Arg::new("inverse")
+                        .short('i')
+                        .long("inverse")
+                        .action(ArgAction::SetTrue)
+                        .help("Keep the records that do not match."),
                 ),
         )
         .subcommand(
@@ -223,6 +943,25 @@ fn main() -> Result<()> {
                         .num_args(0..)
                         .help("Input fasta file path(s)."),
                 )
+                .arg(
+                    Arg::new("rna")
+                        .long("rna")
+                        .action(ArgAction::SetTrue)
+                        .help("Complement A to U rather than T."),
+                )
+                .arg(
+                    Arg::new("complement-only")
+                        .long("complement-only")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("reverse-only")
+                        .help("Complement each record without reversing it."),
+                )
+                .arg(
+                    Arg::new("reverse-only")
+                        .long("reverse-only")
+                        .action(ArgAction::SetTrue)
+                        .help("Reverse each record without complementing it."),
+                )
         )
         .subcommand(
             Command::new("min")
@@ -233,6 +972,83 @@ fn main() -> Result<()> {
                         .value_parser(value_parser!(PathBuf))
                         .num_args(0..)
                         .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("no-revcomp")
+                        .long("no-revcomp")
+                        .action(ArgAction::SetTrue)
+                        .help("Only rotate the forward strand, e.g. for RNA or protein."),
+                ),
+        )
+        .subcommand(
+            Command::new("rotate")
+                .about("Rotate circular sequences by an offset, or to start at an anchor motif.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("offset")
+                        .short('o')
+                        .long("offset")
+                        .value_parser(value_parser!(i64))
+                        .allow_hyphen_values(true)
+                        .num_args(1)
+                        .required_unless_present("anchor")
+                        .conflicts_with("anchor")
+                        .help("Move this many bases from the start to the end. Negative values rotate the other way."),
+                )
+                .arg(
+                    Arg::new("anchor")
+                        .short('a')
+                        .long("anchor")
+                        .num_args(1)
+                        .help("Rotate (and reverse complement if needed) so this (IUPAC) motif reads forward at position 1."),
+                )
+                .arg(
+                    Arg::new("mismatches")
+                        .short('m')
+                        .long("mismatches")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Mismatches allowed in the anchor."),
+                ),
+        )
+        .subcommand(
+            Command::new("circularise")
+                .about("Detect and trim overlapping ends of circular contigs.")
+                .arg(
+                    Arg::new("fasta")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(0..)
+                        .help("Input fasta file path(s)."),
+                )
+                .arg(
+                    Arg::new("min-overlap")
+                        .long("min-overlap")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("50")
+                        .help("Minimum length of the overlap between the ends."),
+                )
+                .arg(
+                    Arg::new("max-overlap")
+                        .long("max-overlap")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .help("Maximum length of the overlap between the ends. Default is half the sequence length."),
+                )
+                .arg(
+                    Arg::new("mismatches")
+                        .short('m')
+                        .long("mismatches")
+                        .value_parser(value_parser!(usize))
+                        .num_args(1)
+                        .default_value("0")
+                        .help("Mismatches allowed in the overlap."),
                 ),
         )
         .subcommand(
@@ -282,15 +1098,51 @@ fn main() -> Result<()> {
         Some(("regex", matches)) => {
             regex::regex_sequences(matches)?;
         }
+        Some(("search", matches)) => {
+            search::search(matches)?;
+        }
+        Some(("grep-seq", matches)) => {
+            grep_seq::grep_seq(matches)?;
+        }
+        Some(("pcr", matches)) => {
+            pcr::pcr(matches)?;
+        }
+        Some(("rename", matches)) => {
+            rename::rename(matches)?;
+        }
         Some(("extract", matches)) => {
             extract::extract_region(matches)?;
         }
+        Some(("digest", matches)) => {
+            digest::digest(matches)?;
+        }
+        Some(("pdigest", matches)) => {
+            pdigest::protease_digest(matches)?;
+        }
         Some(("num", matches)) => {
             number::get_number_seq_bases(matches)?;
         }
         Some(("merge", matches)) => {
             merge::merge_fastas(matches)?;
         }
+        Some(("backtrans", matches)) => {
+            backtrans::back_translate(matches)?;
+        }
+        Some(("dedup", matches)) => {
+            dedup::dedup(matches)?;
+        }
+        Some(("setop", matches)) => {
+            setop::setop(matches)?;
+        }
+        Some(("codon-align", matches)) => {
+            codon_align::codon_align(matches)?;
+        }
+        Some(("dnds", matches)) => {
+            dnds::dnds(matches)?;
+        }
+        Some(("annotate", matches)) => {
+            annotate::annotate(matches)?;
+        }
         Some(("filter", matches)) => {
             filter::filter_sequences(matches)?;
         }
@@ -304,6 +1156,38 @@ fn main() -> Result<()> {
         Some(("min", matches)) => {
             min::min(matches)?;
         }
+        Some(("rotate", matches)) => {
+            rotate::rotate_sequences(matches)?;
+        }
+        Some(("circularise", matches)) => {
+            circularise::circularise(matches)?;
+        }
+        Some(("unmerge", matches)) => {
+            unmerge::unmerge(matches)?;
+        }
+        Some(("gaps", matches)) => {
+            gaps::gaps(matches)?;
+        }
+        Some(("masked", matches)) => {
+            gaps::masked(matches)?;
+        }
+        Some(("mask", matches)) => {
+            mask::mask(matches)?;
+        }
+        Some(("unmask", matches)) => {
+            mask::unmask(matches)?;
+        }
+        Some(("hardmask", matches)) => {
+            mask::hardmask(matches)?;
+        }
+        Some(("break", matches)) => {
+            break_gaps::break_scaffolds(matches)?;
+        }
+        Some(("agp", matches)) => {
+            if let Some(("build", matches)) = matches.subcommand() {
+                agp::build(matches)?;
+            }
+        }
         Some(("split", matches)) => {
             split::split_fasta(matches)?;
         }
@@ -0,0 +1,273 @@
+// lines of an AGP 2.1 file, describing how objects (e.g. scaffolds)
+// are built from components (e.g. contigs) and gaps. See
+// https://www.ncbi.nlm.nih.gov/genbank/genome_agp_specification/
+
+use super::error::AgpError;
+use anyhow::Result;
+use std::fmt;
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+use std::path::Path;
+
+pub const VERSION: &str = "##agp-version\t2.1";
+
+/// Gap types allowed in AGP 2.1.
+pub const GAP_TYPES: &[&str] = &[
+    "scaffold",
+    "contig",
+    "centromere",
+    "short_arm",
+    "heterochromatin",
+    "telomere",
+    "repeat",
+    "contamination",
+];
+
+/// A component or gap, in 1-based closed coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgpLine {
+    pub object: String,
+    pub object_beg: usize,
+    pub object_end: usize,
+    pub part_number: usize,
+    pub part: Part,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Part {
+    Component {
+        // W for WGS contig, A, D, F, G, O, P
+        component_type: char,
+        id: String,
+        beg: usize,
+        end: usize,
+        // +, -, ? or na
+        orientation: String,
+    },
+    Gap {
+        // N for a gap of known length, U for unknown
+        component_type: char,
+        length: usize,
+        gap_type: String,
+        linkage: bool,
+        evidence: String,
+    },
+}
+
+impl AgpLine {
+    pub fn component(
+        object: &str,
+        object_beg: usize,
+        part_number: usize,
+        id: &str,
+        len: usize,
+    ) -> Self {
+        Self {
+            object: object.to_string(),
+            object_beg,
+            object_end: object_beg + len - 1,
+            part_number,
+            part: Part::Component {
+                component_type: 'W',
+                id: id.to_string(),
+                beg: 1,
+                end: len,
+                orientation: "+".into(),
+            },
+        }
+    }
+
+    /// A gap of known length between scaffolded components.
+    pub fn gap(object: &str, object_beg: usize, part_number: usize, length: usize) -> Self {
+        Self {
+            object: object.to_string(),
+            object_beg,
+            object_end: object_beg + length - 1,
+            part_number,
+            part: Part::Gap {
+                component_type: 'N',
+                length,
+                gap_type: "scaffold".into(),
+                linkage: true,
+                evidence: "unspecified".into(),
+            },
+        }
+    }
+
+    /// Parse a (non-comment) line, numbered from 1 for errors.
+    pub fn parse(s: &str, line: usize) -> Result<Self, AgpError> {
+        let fields: Vec<&str> = s.trim_end_matches('\r').split('\t').collect();
+        // the last column is optional for gaps
+        if fields.len() < 8 || fields.len() > 9 {
+            return Err(AgpError::Columns {
+                line,
+                found: fields.len(),
+            });
+        }
+        let number = |i: usize, field: &'static str| {
+            fields[i]
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| AgpError::Field {
+                    line,
+                    field,
+                    value: fields[i].to_string(),
+                })
+        };
+        let text = |i: usize| {
+            fields
+                .get(i)
+                .map(|f| f.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let component_type = match fields[4].trim() {
+            t if t.len() == 1 => t.chars().next().unwrap(),
+            t => {
+                return Err(AgpError::Field {
+                    line,
+                    field: "component type",
+                    value: t.to_string(),
+                })
+            }
+        };
+        let part = match component_type {
+            'N' | 'U' => Part::Gap {
+                component_type,
+                length: number(5, "gap length")?,
+                gap_type: text(6),
+                linkage: match fields[7].trim() {
+                    "yes" => true,
+                    "no" => false,
+                    l => {
+                        return Err(AgpError::Field {
+                            line,
+                            field: "linkage",
+                            value: l.to_string(),
+                        })
+                    }
+                },
+                evidence: text(8),
+            },
+            _ if fields.len() < 9 => {
+                return Err(AgpError::Columns {
+                    line,
+                    found: fields.len(),
+                })
+            }
+            _ => Part::Component {
+                component_type,
+                id: text(5),
+                beg: number(6, "component start")?,
+                end: number(7, "component end")?,
+                orientation: match fields.get(8).map(|o| o.trim()) {
+                    Some(o) if ["+", "-", "?", "0", "na"].contains(&o) => o.to_string(),
+                    o => {
+                        return Err(AgpError::Field {
+                            line,
+                            field: "orientation",
+                            value: o.unwrap_or_default().to_string(),
+                        })
+                    }
+                },
+            },
+        };
+
+        let agp_line = Self {
+            object: text(0),
+            object_beg: number(1, "object start")?,
+            object_end: number(2, "object end")?,
+            part_number: number(3, "part number")?,
+            part,
+        };
+        // coordinates are 1-based, and each span runs forwards
+        let field = |field: &'static str, value: usize| AgpError::Range { line, field, value };
+        if agp_line.object_beg == 0 {
+            return Err(field("object start", agp_line.object_beg));
+        }
+        if agp_line.object_end < agp_line.object_beg {
+            return Err(field("object end", agp_line.object_end));
+        }
+        if let Part::Component { beg, end, .. } = &agp_line.part {
+            if *beg == 0 {
+                return Err(field("component start", *beg));
+            }
+            if end < beg {
+                return Err(field("component end", *end));
+            }
+        }
+
+        // the object span has to agree with the part
+        let len = match &agp_line.part {
+            Part::Component { beg, end, .. } => end - beg + 1,
+            Part::Gap { length, .. } => *length,
+        };
+        if agp_line.object_end - agp_line.object_beg + 1 != len {
+            return Err(AgpError::Length {
+                line,
+                object: agp_line.object,
+                beg: agp_line.object_beg,
+                end: agp_line.object_end,
+                len,
+            });
+        }
+        Ok(agp_line)
+    }
+
+    /// Whether the component is placed reverse complemented.
+    pub fn is_reverse(&self) -> bool {
+        matches!(&self.part, Part::Component { orientation, .. } if orientation == "-")
+    }
+}
+
+/// Every line of an AGP file, skipping comments and blank lines.
+pub fn read_agp(path: impl AsRef<Path>) -> Result<Vec<AgpLine>> {
+    let file = File::open(path)?;
+    let mut lines = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        lines.push(AgpLine::parse(&line, i + 1)?);
+    }
+    Ok(lines)
+}
+
+impl fmt::Display for AgpLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t",
+            self.object, self.object_beg, self.object_end, self.part_number
+        )?;
+        match &self.part {
+            Part::Component {
+                component_type,
+                id,
+                beg,
+                end,
+                orientation,
+            } => write!(
+                f,
+                "{}\t{}\t{}\t{}\t{}",
+                component_type, id, beg, end, orientation
+            ),
+            Part::Gap {
+                component_type,
+                length,
+                gap_type,
+                linkage,
+                evidence,
+            } => write!(
+                f,
+                "{}\t{}\t{}\t{}\t{}",
+                component_type,
+                length,
+                gap_type,
+                if *linkage { "yes" } else { "no" },
+                evidence
+            ),
+        }
+    }
+}
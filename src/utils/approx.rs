@@ -0,0 +1,195 @@
+// approximate matching of short (IUPAC) oligos against
+// longer sequences, allowing mismatches or indels
+
+use super::iupac;
+
+/// A match of a pattern in a text, with a half-open interval
+/// and the number of mismatches/edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub start: usize,
+    pub end: usize,
+    pub dist: usize,
+}
+
+// A text base matches a pattern base when the bases it stands
+// for are a subset of the pattern's, so degenerate primers match
+// but runs of N in the text do not.
+fn is_match(pattern_mask: u8, text_mask: u8) -> bool {
+    text_mask != 0 && text_mask & !pattern_mask == 0
+}
+
+/// Number of mismatches between a pattern and an equal length text.
+pub fn mismatches(pattern: &[u8], text: &[u8]) -> usize {
+    pattern
+        .iter()
+        .zip(text)
+        .filter(|(p, t)| !is_match(iupac::to_mask(**p), iupac::to_mask(**t)))
+        .count()
+}
+
+/// Whether a pattern and an equal length text differ by at most
+/// `k` mismatches, stopping early once there are more.
+pub fn within(pattern: &[u8], text: &[u8], k: usize) -> bool {
+    pattern
+        .iter()
+        .zip(text)
+        .filter(|(p, t)| !is_match(iupac::to_mask(**p), iupac::to_mask(**t)))
+        .nth(k)
+        .is_none()
+}
+
+/// Every position where the pattern matches with at most `k` mismatches.
+pub fn hamming(pattern: &[u8], text: &[u8], k: usize) -> Vec<Hit> {
+    let m = pattern.len();
+    let mut hits = Vec::new();
+    if m == 0 || m > text.len() {
+        return hits;
+    }
+    let pattern: Vec<u8> = pattern.iter().map(|c| iupac::to_mask(*c)).collect();
+
+    for start in 0..=text.len() - m {
+        let mut dist = 0;
+        for (p, t) in pattern.iter().zip(&text[start..start + m]) {
+            if !is_match(*p, iupac::to_mask(*t)) {
+                dist += 1;
+                if dist > k {
+                    break;
+                }
+            }
+        }
+        if dist <= k {
+            hits.push(Hit {
+                start,
+                end: start + m,
+                dist,
+            });
+        }
+    }
+    hits
+}
+
+/// Matches with an edit distance (mismatches, insertions and
+/// deletions) of at most `k`. Of a run of overlapping matches
+/// only the best is reported.
+pub fn levenshtein(pattern: &[u8], text: &[u8], k: usize) -> Vec<Hit> {
+    let m = pattern.len();
+    if m == 0 {
+        return Vec::new();
+    }
+    let masks: Vec<u8> = pattern.iter().map(|c| iupac::to_mask(*c)).collect();
+
+    // end positions (inclusive) and distances
+    let ends = match m <= 64 {
+        true => myers(&masks, text, k),
+        false => sellers(&masks, text, k)
+            .into_iter()
+            .map(|h| (h.end - 1, h.dist))
+            .collect(),
+    };
+
+    // keep the best end of each run of consecutive ends, and
+    // find its start by aligning the pattern to the text before it
+    let mut hits = Vec::new();
+    let mut i = 0;
+    while i < ends.len() {
+        let mut best = ends[i];
+        let mut j = i + 1;
+        while j < ends.len() && ends[j].0 == ends[j - 1].0 + 1 {
+            if ends[j].1 < best.1 {
+                best = ends[j];
+            }
+            j += 1;
+        }
+        let end = best.0 + 1;
+        let window = end.saturating_sub(m + k);
+        if let Some(hit) = sellers(&masks, &text[window..end], k)
+            .into_iter()
+            .rfind(|h| h.end == end - window)
+        {
+            hits.push(Hit {
+                start: window + hit.start,
+                end,
+                dist: hit.dist,
+            });
+        }
+        i = j;
+    }
+    hits
+}
+
+// Myers (1999) bit-parallel approximate matching, for
+// patterns of up to 64 bases.
+fn myers(masks: &[u8], text: &[u8], k: usize) -> Vec<(usize, usize)> {
+    let m = masks.len();
+    let mut peq = [0u64; 16];
+    for (text_mask, eq) in peq.iter_mut().enumerate() {
+        for (i, p) in masks.iter().enumerate() {
+            if is_match(*p, text_mask as u8) {
+                *eq |= 1 << i;
+            }
+        }
+    }
+
+    let high = 1u64 << (m - 1);
+    let mut pv = if m == 64 { !0 } else { (1u64 << m) - 1 };
+    let mut mv = 0u64;
+    let mut score = m;
+    let mut ends = Vec::new();
+
+    for (j, c) in text.iter().enumerate() {
+        let eq = peq[iupac::to_mask(*c) as usize];
+        let xv = eq | mv;
+        let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+        let mut ph = mv | !(xh | pv);
+        let mut mh = pv & xh;
+        if ph & high != 0 {
+            score += 1;
+        } else if mh & high != 0 {
+            score -= 1;
+        }
+        ph <<= 1;
+        mh <<= 1;
+        pv = mh | !(xv | ph);
+        mv = ph & xv;
+        if score <= k {
+            ends.push((j, score));
+        }
+    }
+    ends
+}
+
+// Sellers (1980) dynamic programming, where the pattern has to be
+// aligned in full but may start anywhere in the text. Each cell keeps
+// the text position its alignment started at.
+fn sellers(masks: &[u8], text: &[u8], k: usize) -> Vec<Hit> {
+    let m = masks.len();
+    // (distance, start) for the previous and current text column
+    let mut prev: Vec<(usize, usize)> = (0..=m).map(|i| (i, 0)).collect();
+    let mut curr = prev.clone();
+    let mut hits = Vec::new();
+
+    for (j, c) in text.iter().enumerate() {
+        let text_mask = iupac::to_mask(*c);
+        curr[0] = (0, j + 1);
+        for i in 1..=m {
+            let cost = usize::from(!is_match(masks[i - 1], text_mask));
+            let diag = (prev[i - 1].0 + cost, prev[i - 1].1);
+            let up = (curr[i - 1].0 + 1, curr[i - 1].1);
+            let left = (prev[i].0 + 1, prev[i].1);
+            curr[i] = [diag, up, left]
+                .into_iter()
+                .min_by_key(|(d, s)| (*d, std::cmp::Reverse(*s)))
+                .expect("three candidates");
+        }
+        if curr[m].0 <= k {
+            hits.push(Hit {
+                start: curr[m].1,
+                end: j + 1,
+                dist: curr[m].0,
+            });
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    hits
+}
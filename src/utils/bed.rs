@@ -0,0 +1,54 @@
+// reading BED intervals, grouped by chromosome
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+use std::path::Path;
+
+/// Half-open, 0-based intervals per chromosome, sorted and
+/// with overlapping or touching intervals merged.
+pub fn read_bed(path: impl AsRef<Path>) -> Result<HashMap<String, Vec<(usize, usize)>>> {
+    let file = File::open(path)?;
+    let mut intervals: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty()
+            || line.starts_with('#')
+            || line.starts_with("track ")
+            || line.starts_with("browser ")
+        {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (start, end) = match (
+            fields.get(1).and_then(|s| s.trim().parse::<usize>().ok()),
+            fields.get(2).and_then(|e| e.trim().parse::<usize>().ok()),
+        ) {
+            (Some(s), Some(e)) if s <= e => (s, e),
+            _ => bail!(
+                "[-]\tBED line {} needs a chromosome, start and end (start <= end).",
+                i + 1
+            ),
+        };
+        intervals
+            .entry(fields[0].to_string())
+            .or_default()
+            .push((start, end));
+    }
+
+    for chrom in intervals.values_mut() {
+        chrom.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(chrom.len());
+        for (start, end) in chrom.drain(..) {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        *chrom = merged;
+    }
+    Ok(intervals)
+}
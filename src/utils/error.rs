@@ -34,3 +34,66 @@ pub enum RegionError {
     #[error("[-]\tCould not extract region. Is the range overlapping zero, or larger than the length of the chromosome?")]
     SeqExtractError,
 }
+
+#[derive(Error, Debug)]
+pub enum AgpError {
+    #[error("[-]\tAGP line {line}: expected 8 or 9 tab separated columns (9 for components), found {found}.")]
+    Columns { line: usize, found: usize },
+    #[error("[-]\tAGP line {line}: could not parse {field} ({value}).")]
+    Field {
+        line: usize,
+        field: &'static str,
+        value: String,
+    },
+    #[error("[-]\tAGP line {line}: {field} {value} is out of range. Coordinates start at 1, and a start cannot be after its end.")]
+    Range {
+        line: usize,
+        field: &'static str,
+        value: usize,
+    },
+    #[error("[-]\tAGP line {line}: {object} spans {beg}-{end}, but the part is {len} long.")]
+    Length {
+        line: usize,
+        object: String,
+        beg: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("[-]\t{object} part {part}: component {id} is not in the contigs.")]
+    MissingComponent {
+        object: String,
+        part: usize,
+        id: String,
+    },
+    #[error("[-]\t{object} part {part}: component {id} ends at {end}, but is only {len} long.")]
+    ComponentRange {
+        object: String,
+        part: usize,
+        id: String,
+        end: usize,
+        len: usize,
+    },
+    #[error("[-]\t{object} part {part}: starts at {beg}, but the previous part ended at {prev}.")]
+    NotContiguous {
+        object: String,
+        part: usize,
+        beg: usize,
+        prev: usize,
+    },
+    #[error(
+        "[-]\t{object}: part {part} follows part {prev}, parts must be numbered from 1 in order."
+    )]
+    PartNumber {
+        object: String,
+        part: usize,
+        prev: usize,
+    },
+    #[error("[-]\t{object}: lines are not together in the AGP.")]
+    SplitObject { object: String },
+    #[error("[-]\t{object} part {part}: unknown gap type {gap_type}.")]
+    GapType {
+        object: String,
+        part: usize,
+        gap_type: String,
+    },
+}
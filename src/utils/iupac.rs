@@ -0,0 +1,78 @@
+// IUPAC nucleotide codes represented as 4-bit masks,
+// one bit for each of A, C, G and T/U.
+
+pub const A: u8 = 0b0001;
+pub const C: u8 = 0b0010;
+pub const G: u8 = 0b0100;
+pub const T: u8 = 0b1000;
+
+/// The set of bases a (case-insensitive) IUPAC symbol stands
+/// for. Anything that is not a nucleotide code returns 0.
+pub fn to_mask(c: u8) -> u8 {
+    match c.to_ascii_uppercase() {
+        b'A' => A,
+        b'C' => C,
+        b'G' => G,
+        b'T' | b'U' => T,
+        b'R' => A | G,
+        b'Y' => C | T,
+        b'S' => C | G,
+        b'W' => A | T,
+        b'K' => G | T,
+        b'M' => A | C,
+        b'B' => C | G | T,
+        b'D' => A | G | T,
+        b'H' => A | C | T,
+        b'V' => A | C | G,
+        b'N' => A | C | G | T,
+        _ => 0,
+    }
+}
+
+/// The (uppercase) IUPAC symbol for a set of bases.
+pub fn from_mask(mask: u8) -> u8 {
+    match mask & 0b1111 {
+        A => b'A',
+        C => b'C',
+        G => b'G',
+        T => b'T',
+        0b0101 => b'R',
+        0b1010 => b'Y',
+        0b0110 => b'S',
+        0b1001 => b'W',
+        0b1100 => b'K',
+        0b0011 => b'M',
+        0b1110 => b'B',
+        0b1101 => b'D',
+        0b1011 => b'H',
+        0b0111 => b'V',
+        _ => b'N',
+    }
+}
+
+/// Number of bases a symbol stands for.
+pub fn degeneracy(c: u8) -> u32 {
+    to_mask(c).count_ones()
+}
+
+/// Turn an IUPAC motif into an equivalent regex, e.g.
+/// `GCWGC` into `GC[AT]GC`. Matching should be case-insensitive.
+pub fn motif_to_regex(motif: &str) -> String {
+    let mut re = String::with_capacity(motif.len() * 4);
+    for c in motif.chars() {
+        let mask = if c.is_ascii() { to_mask(c as u8) } else { 0 };
+        match mask {
+            0 => re.push_str(&regex::escape(&c.to_string())),
+            _ => {
+                re.push('[');
+                for (bit, bases) in [(A, "A"), (C, "C"), (G, "G"), (T, "TU")] {
+                    if mask & bit != 0 {
+                        re.push_str(bases);
+                    }
+                }
+                re.push(']');
+            }
+        }
+    }
+    re
+}
@@ -0,0 +1,44 @@
+// hashed keys for comparing records by ID or sequence,
+// so only a u64 per record needs to be held in memory
+
+use super::lex_min::lex_min;
+use anyhow::Result;
+use noodles_fasta::Record;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[derive(Clone, Copy)]
+pub enum KeyBy {
+    Id,
+    // the sequence, ignoring case
+    Seq,
+    // the minimal rotation over both strands, ignoring case
+    Canonical,
+}
+
+impl KeyBy {
+    pub fn new(by: &str) -> Self {
+        match by {
+            "seq" => Self::Seq,
+            "canonical" => Self::Canonical,
+            _ => Self::Id,
+        }
+    }
+}
+
+pub fn record_key(record: &Record, by: KeyBy) -> Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    match by {
+        KeyBy::Id => record.name().hash(&mut hasher),
+        KeyBy::Seq => record
+            .sequence()
+            .as_ref()
+            .to_ascii_uppercase()
+            .hash(&mut hasher),
+        KeyBy::Canonical => {
+            let seq = record.sequence().as_ref().to_ascii_uppercase();
+            lex_min(&seq, true).seq.hash(&mut hasher)
+        }
+    }
+    Ok(hasher.finish())
+}
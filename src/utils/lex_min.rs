@@ -1,23 +1,24 @@
 use super::revcomp::reverse_complement;
-use anyhow::Result;
-use lexical_sort::{natural_lexical_cmp, StringSort};
-use std::cmp::min;
+use std::cmp::{min, Ordering};
 
 /// Booth's algorithm for the lexicographically minimal
 /// rotation of a string. Should give us a canonical rotation
-/// given a rotated string.
+/// given a rotated string. Bytes are compared ignoring case.
 /// Taken from [here](https://github.com/zimpha/algorithmic-library/blob/61e897983314033615bcd278d22a754bfc3c3f22/rust/src/strings/mod.rs)
-fn minimal_rotation<T: Ord>(s: &[T]) -> usize {
+fn minimal_rotation(s: &[u8]) -> usize {
     let n = s.len();
+    if n == 0 {
+        return 0;
+    }
     let mut i = 0;
     let mut j = 1;
     loop {
         let mut k = 0;
-        let mut ci = &s[i % n];
-        let mut cj = &s[j % n];
+        let mut ci = s[i % n].to_ascii_uppercase();
+        let mut cj = s[j % n].to_ascii_uppercase();
         while k < n {
-            ci = &s[(i + k) % n];
-            cj = &s[(j + k) % n];
+            ci = s[(i + k) % n].to_ascii_uppercase();
+            cj = s[(j + k) % n].to_ascii_uppercase();
             if ci != cj {
                 break;
             }
@@ -36,24 +37,63 @@ fn minimal_rotation<T: Ord>(s: &[T]) -> usize {
     }
 }
 
-pub fn lex_min(dna_string: &[u8]) -> Result<String> {
-    // revcomp
-    let dna_string_r = reverse_complement(dna_string);
-    // index for forward and reverse
+/// The sequence rotated left by `offset`, so it starts at that
+/// (0-based) position.
+pub fn rotate(seq: &[u8], offset: usize) -> Vec<u8> {
+    if seq.is_empty() {
+        return Vec::new();
+    }
+    let offset = offset % seq.len();
+    [&seq[offset..], &seq[..offset]].concat()
+}
+
+/// The canonical rotation of a sequence.
+pub struct MinRotation {
+    pub seq: Vec<u8>,
+    /// Whether the rotation is of the reverse complement.
+    pub reverse: bool,
+    /// Where the rotation starts, in the strand it was taken from.
+    pub offset: usize,
+}
+
+impl MinRotation {
+    pub fn strand(&self) -> char {
+        match self.reverse {
+            true => '-',
+            false => '+',
+        }
+    }
+}
+
+/// The lexicographically minimal rotation of a sequence, ignoring
+/// case, over both strands unless `revcomp` is false. Ties go to
+/// the forward strand.
+pub fn lex_min(dna_string: &[u8], revcomp: bool) -> MinRotation {
     let index_f = minimal_rotation(dna_string);
+    let forward = MinRotation {
+        seq: rotate(dna_string, index_f),
+        reverse: false,
+        offset: index_f,
+    };
+    if !revcomp {
+        return forward;
+    }
+
+    let dna_string_r = reverse_complement(dna_string);
     let index_r = minimal_rotation(&dna_string_r);
-    // create the substrings
-    // starts
-    let start_f = &dna_string[index_f..];
-    let start_r = &dna_string_r[index_r..];
-    // ends
-    let end_f = &dna_string[0..index_f];
-    let end_r = &dna_string_r[0..index_r];
-    // string
-    let f = String::from_utf8([start_f, end_f].concat())?;
-    let r = String::from_utf8([start_r, end_r].concat())?;
+    let reverse = MinRotation {
+        seq: rotate(&dna_string_r, index_r),
+        reverse: true,
+        offset: index_r,
+    };
 
-    let mut strings = [&f, &r];
-    strings.string_sort_unstable(natural_lexical_cmp);
-    Ok(strings[0].to_string())
+    let cmp = forward
+        .seq
+        .iter()
+        .map(u8::to_ascii_uppercase)
+        .cmp(reverse.seq.iter().map(u8::to_ascii_uppercase));
+    match cmp {
+        Ordering::Greater => reverse,
+        _ => forward,
+    }
 }
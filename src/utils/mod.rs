@@ -1,6 +1,12 @@
+pub mod agp;
+pub mod approx;
+pub mod bed;
 pub mod error;
+pub mod iupac;
+pub mod key;
 pub mod lex_min;
 pub mod parse;
 pub mod revcomp;
+pub mod runs;
 pub mod stdin;
 pub mod translate;
@@ -1,28 +1,79 @@
+// Complement bases using precomputed static lookup tables.
+// IUPAC codes are complemented (R <-> Y, K <-> M, B <-> V, D <-> H),
+// case is preserved, and anything else (e.g. gaps) is left as is.
+static COMPLEMENT_DNA: [u8; 256] = make_complement_table(b'T');
+static COMPLEMENT_RNA: [u8; 256] = make_complement_table(b'U');
+
+/// Whether complemented adenines are written as T or U.
+#[derive(Clone, Copy)]
+pub enum Alphabet {
+    Dna,
+    Rna,
+}
+
+// Complement of a single base.
+pub fn complement(base: u8, alphabet: Alphabet) -> u8 {
+    match alphabet {
+        Alphabet::Dna => COMPLEMENT_DNA[base as usize],
+        Alphabet::Rna => COMPLEMENT_RNA[base as usize],
+    }
+}
+
 // Reverse complement a string slice.
 pub fn reverse_complement(dna: &[u8]) -> Vec<u8> {
-    let dna_vec = dna.to_vec();
-    let mut revcomp = Vec::new();
+    let mut revcomp = dna.to_vec();
+    revcomp_inplace(&mut revcomp, Alphabet::Dna);
+    revcomp
+}
+
+// Reverse complement a sequence in place.
+pub fn revcomp_inplace(seq: &mut [u8], alphabet: Alphabet) {
+    seq.reverse();
+    complement_inplace(seq, alphabet);
+}
 
-    for base in dna_vec.iter() {
-        revcomp.push(switch_base(*base))
+// Complement a sequence in place, without reversing it.
+pub fn complement_inplace(seq: &mut [u8], alphabet: Alphabet) {
+    for base in seq.iter_mut() {
+        *base = complement(*base, alphabet);
     }
-    revcomp.as_mut_slice().reverse();
-    revcomp
 }
 
-// Used in `reverse_complement` to switch to a complementary base.
-fn switch_base(c: u8) -> u8 {
-    match c {
-        b'A' => b'T',
-        b'a' => b't',
-        b'C' => b'G',
-        b'c' => b'g',
-        b'T' => b'A',
-        b't' => b'a',
-        b'G' => b'C',
-        b'g' => b'c',
-        b'N' => b'N',
-        b'n' => b'n',
-        _ => b'N',
+// Const function to generate the complement lookup table at compile time,
+// with `t` the complement of A (T for DNA, U for RNA).
+const fn make_complement_table(t: u8) -> [u8; 256] {
+    let mut table = [0u8; 256];
+
+    // Handle unknown characters by mapping them to themselves
+    let mut i = 0;
+    while i < 256 {
+        table[i] = i as u8;
+        i += 1;
+    }
+
+    let pairs: [(u8, u8); 15] = [
+        (b'A', t),
+        (b'C', b'G'),
+        (b'G', b'C'),
+        (b'T', b'A'),
+        (b'U', b'A'),
+        (b'R', b'Y'),
+        (b'Y', b'R'),
+        (b'K', b'M'),
+        (b'M', b'K'),
+        (b'B', b'V'),
+        (b'V', b'B'),
+        (b'D', b'H'),
+        (b'H', b'D'),
+        (b'S', b'S'),
+        (b'W', b'W'),
+    ];
+    let mut i = 0;
+    while i < pairs.len() {
+        let (base, comp) = pairs[i];
+        table[base as usize] = comp;
+        table[base.to_ascii_lowercase() as usize] = comp.to_ascii_lowercase();
+        i += 1;
     }
+    table
 }
@@ -0,0 +1,32 @@
+// runs of bases sharing a property, such as gaps (N)
+// or soft-masked (lowercase) stretches
+
+/// Half-open, 0-based intervals of runs of at least `min_len`
+/// bases for which `pred` is true.
+pub fn runs(seq: &[u8], min_len: usize, pred: impl Fn(u8) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (i, base) in seq.iter().enumerate() {
+        match (pred(*base), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                if i - s >= min_len.max(1) {
+                    runs.push((s, i));
+                }
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        if seq.len() - s >= min_len.max(1) {
+            runs.push((s, seq.len()));
+        }
+    }
+    runs
+}
+
+/// Whether a base is a gap.
+pub fn is_gap(base: u8) -> bool {
+    base == b'N' || base == b'n'
+}
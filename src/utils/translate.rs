@@ -1,6 +1,8 @@
 // thanks
 // https://github.com/dweb0/protein-translate/blob/master/src/lib.rs
 
+use super::iupac;
+
 pub fn translate(seq: &[u8]) -> Vec<u8> {
     let mut peptide = Vec::with_capacity(seq.len() / 3);
 
@@ -30,6 +32,197 @@ pub fn translate(seq: &[u8]) -> Vec<u8> {
     peptide
 }
 
+/// All codons which translate to a given amino acid (or `*`),
+/// i.e. the inverse of the genetic code table.
+/// The ambiguity codes B (D/N), Z (E/Q) and J (I/L) return the
+/// codons of both amino acids.
+pub fn codons(amino_acid: u8) -> Vec<[u8; 3]> {
+    let amino_acids = match amino_acid.to_ascii_uppercase() {
+        b'B' => vec!['D', 'N'],
+        b'Z' => vec!['E', 'Q'],
+        b'J' => vec!['I', 'L'],
+        aa => vec![aa as char],
+    };
+    let mut codons = Vec::new();
+    for (i, table) in AA_TABLE_CANONICAL.iter().enumerate() {
+        for (j, row) in table.iter().enumerate() {
+            for (k, aa) in row.iter().enumerate() {
+                if amino_acids.contains(aa) {
+                    codons.push([INDEX_TO_BASE[i], INDEX_TO_BASE[j], INDEX_TO_BASE[k]]);
+                }
+            }
+        }
+    }
+    codons
+}
+
+/// The IUPAC degenerate codon covering every codon of an amino
+/// acid. Where the codons of an amino acid are not contiguous
+/// (e.g. S, L, R) the degenerate codon covers some other codons
+/// too.
+pub fn degenerate_codon(amino_acid: u8) -> Option<[u8; 3]> {
+    let codons = codons(amino_acid);
+    if codons.is_empty() {
+        return None;
+    }
+    let mut masks = [0u8; 3];
+    for codon in codons.iter() {
+        for (mask, base) in masks.iter_mut().zip(codon) {
+            *mask |= iupac::to_mask(*base);
+        }
+    }
+    Some(masks.map(iupac::from_mask))
+}
+
+/// Translate a single codon, returning `None` if it contains
+/// anything other than A, C, G or T/U.
+pub fn translate_codon(codon: &[u8]) -> Option<u8> {
+    let mut index = [0; 3];
+    for (i, c) in codon.iter().take(3).enumerate() {
+        index[i] = *ASCII_TO_INDEX.get(*c as usize)?;
+        if index[i] == 4 {
+            return None;
+        }
+    }
+    Some(AA_TABLE_CANONICAL[index[0]][index[1]][index[2]] as u8)
+}
+
+// uppercase DNA codon
+fn normalise(codon: &[u8]) -> [u8; 3] {
+    let norm = |c: u8| match c.to_ascii_uppercase() {
+        b'U' => b'T',
+        c => c,
+    };
+    [norm(codon[0]), norm(codon[1]), norm(codon[2])]
+}
+
+/// Both bases are purines, or both are pyrimidines.
+pub fn is_transition(a: u8, b: u8) -> bool {
+    let purine = |c: u8| matches!(c.to_ascii_uppercase(), b'A' | b'G');
+    purine(a) == purine(b)
+}
+
+/// Synonymous and non-synonymous sites of a codon (Nei & Gojobori, 1986),
+/// with transitions weighted by `kappa` (1.0 gives the unweighted sites).
+/// Changes to stop codons count as non-synonymous.
+pub fn codon_sites(codon: &[u8], kappa: f64) -> Option<(f64, f64)> {
+    let aa = translate_codon(codon)?;
+    let codon = normalise(codon);
+    let mut syn = 0.0;
+    for pos in 0..3 {
+        let mut mutant = codon;
+        for base in INDEX_TO_BASE {
+            if base == codon[pos] {
+                continue;
+            }
+            mutant[pos] = base;
+            let weight = if is_transition(codon[pos], base) {
+                kappa
+            } else {
+                1.0
+            };
+            if aa != b'*' && translate_codon(&mutant) == Some(aa) {
+                syn += weight / (kappa + 2.0);
+            }
+        }
+    }
+    Some((syn, 3.0 - syn))
+}
+
+/// Synonymous and non-synonymous differences between two codons,
+/// split into transitions and transversions.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CodonDifferences {
+    pub syn_ts: f64,
+    pub syn_tv: f64,
+    pub nonsyn_ts: f64,
+    pub nonsyn_tv: f64,
+}
+
+impl CodonDifferences {
+    pub fn syn(&self) -> f64 {
+        self.syn_ts + self.syn_tv
+    }
+    pub fn nonsyn(&self) -> f64 {
+        self.nonsyn_ts + self.nonsyn_tv
+    }
+}
+
+impl std::ops::AddAssign for CodonDifferences {
+    fn add_assign(&mut self, other: Self) {
+        self.syn_ts += other.syn_ts;
+        self.syn_tv += other.syn_tv;
+        self.nonsyn_ts += other.nonsyn_ts;
+        self.nonsyn_tv += other.nonsyn_tv;
+    }
+}
+
+/// Count the differences between two codons. Where they differ at
+/// more than one position, the differences are averaged over every
+/// mutational pathway which does not pass through a stop codon.
+pub fn codon_differences(a: &[u8], b: &[u8]) -> Option<CodonDifferences> {
+    translate_codon(a)?;
+    translate_codon(b)?;
+    let (a, b) = (normalise(a), normalise(b));
+
+    let positions: Vec<usize> = (0..3).filter(|i| a[*i] != b[*i]).collect();
+    let pathways: Vec<Vec<usize>> = match positions[..] {
+        [] => return Some(CodonDifferences::default()),
+        [i] => vec![vec![i]],
+        [i, j] => vec![vec![i, j], vec![j, i]],
+        [i, j, k] => vec![
+            vec![i, j, k],
+            vec![i, k, j],
+            vec![j, i, k],
+            vec![j, k, i],
+            vec![k, i, j],
+            vec![k, j, i],
+        ],
+        _ => unreachable!(),
+    };
+
+    let walk = |pathway: &[usize], avoid_stops: bool| -> Option<CodonDifferences> {
+        let mut diffs = CodonDifferences::default();
+        let mut current = a;
+        for (step, pos) in pathway.iter().enumerate() {
+            let mut next = current;
+            next[*pos] = b[*pos];
+            let (from, to) = (translate_codon(&current)?, translate_codon(&next)?);
+            if avoid_stops && step + 1 < pathway.len() && to == b'*' {
+                return None;
+            }
+            let ts = is_transition(current[*pos], next[*pos]);
+            match (from == to, ts) {
+                (true, true) => diffs.syn_ts += 1.0,
+                (true, false) => diffs.syn_tv += 1.0,
+                (false, true) => diffs.nonsyn_ts += 1.0,
+                (false, false) => diffs.nonsyn_tv += 1.0,
+            }
+            current = next;
+        }
+        Some(diffs)
+    };
+
+    let mut valid: Vec<CodonDifferences> = pathways.iter().filter_map(|p| walk(p, true)).collect();
+    if valid.is_empty() {
+        valid = pathways.iter().filter_map(|p| walk(p, false)).collect();
+    }
+
+    let n = valid.len() as f64;
+    let mut total = CodonDifferences::default();
+    for d in valid {
+        total += d;
+    }
+    Some(CodonDifferences {
+        syn_ts: total.syn_ts / n,
+        syn_tv: total.syn_tv / n,
+        nonsyn_ts: total.nonsyn_ts / n,
+        nonsyn_tv: total.nonsyn_tv / n,
+    })
+}
+
+static INDEX_TO_BASE: [u8; 4] = [b'A', b'C', b'G', b'T'];
+
 static AA_TABLE_CANONICAL: [[[char; 4]; 4]; 4] = [
     [
         ['K', 'N', 'K', 'N'], // AAA, AAC, AAG, AAU/AAT